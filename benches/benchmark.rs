@@ -9,6 +9,13 @@ fn benchmark_many_spheres(c: &mut Criterion) {
     });
 }
 
+fn benchmark_many_spheres_linear(c: &mut Criterion) {
+    let world = many_spheres::create_linear_world();
+    c.bench_function("many_spheres_linear", |b| {
+        b.iter(|| many_spheres::create_camera(&world))
+    });
+}
+
 fn bench_settings() -> Criterion {
     Criterion::default().sample_size(10)
 }
@@ -16,13 +23,13 @@ fn bench_settings() -> Criterion {
 criterion_group! {
     name = benches;
     config = bench_settings();
-    targets = benchmark_many_spheres
+    targets = benchmark_many_spheres, benchmark_many_spheres_linear
 }
 
 criterion_group! {
     name = many_spheres;
     config = bench_settings();
-    targets = benchmark_many_spheres
+    targets = benchmark_many_spheres, benchmark_many_spheres_linear
 }
 
 criterion_main!(benches);
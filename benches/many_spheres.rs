@@ -6,6 +6,14 @@ use rust_tracer::{
 };
 
 pub fn create_world() -> BoundingVolumeHierarchyNode {
+    BoundingVolumeHierarchyNode::new(create_objects())
+}
+
+pub fn create_linear_world() -> LinearBoundingVolumeHierarchy {
+    LinearBoundingVolumeHierarchy::new(create_objects())
+}
+
+fn create_objects() -> Vec<Box<dyn Hittable>> {
     let ground_texture = Texture::Checker(Checker::with_solid(
         0.32,
         DVec3::new(0.2, 0.3, 0.1),
@@ -75,10 +83,10 @@ pub fn create_world() -> BoundingVolumeHierarchyNode {
         }
     }
 
-    BoundingVolumeHierarchyNode::new(objects)
+    objects
 }
 
-pub fn create_camera(world: &BoundingVolumeHierarchyNode) {
+pub fn create_camera(world: &dyn Hittable) {
     let look_from = DVec3::new(13.0, 2.0, 3.0);
     let image = Image::from_width_aspect_ratio(400, 16.0 / 9.0, 255);
 
@@ -39,8 +39,8 @@ fn main() {
 
     camera.render_image_with_progress(&world);
 
-    match camera.save_image("checker_spheres") {
+    match camera.save_image("images/checker_spheres.png") {
         Ok(_) => println!("Image saved successfully!"),
-        Err(_) => println!("Failed to save the image!"),
+        Err(error) => println!("Failed to save the image: {error}"),
     }
 }
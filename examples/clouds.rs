@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use rust_tracer::prelude::*;
+
+fn main() {
+    let ground = Arc::new(Lambertian::with_solid(DVec3::new(0.4, 0.5, 0.3)));
+
+    let cloud = HeterogeneousMedium::new(
+        Arc::new(Sphere::stationary(
+            DVec3::new(-1.5, 2.0, 0.0),
+            1.5,
+            ground.clone(),
+        )),
+        Density::Noise(NoiseDensity::new(4.0, 1.5)),
+        Texture::Solid(DVec3::ONE),
+    )
+    .with_phase_function(Arc::new(HenyeyGreenstein::with_solid(
+        DVec3::splat(0.9),
+        0.6,
+    )));
+
+    // A soft ball of colored smoke baked into a voxel grid, thinning out towards its edge.
+    let resolution = 32;
+    let data = (0..resolution * resolution * resolution)
+        .map(|index| {
+            let voxel = DVec3::new(
+                (index % resolution) as f64,
+                (index / resolution % resolution) as f64,
+                (index / (resolution * resolution)) as f64,
+            );
+            let distance = ((voxel + 0.5) / resolution as f64 - 0.5).length() * 2.0;
+
+            (1.0 - distance).max(0.0) as f32
+        })
+        .collect();
+    let smoke = HeterogeneousMedium::from_grid(
+        DensityGrid::new(
+            [resolution; 3],
+            data,
+            DVec3::new(0.5, 0.5, -1.5),
+            DVec3::new(3.5, 3.5, 1.5),
+        )
+        .with_scale(6.0),
+        Texture::Solid(DVec3::ONE),
+    )
+    // Absorbs blue the most and scatters evenly, leaving an orange tint.
+    .with_coefficients(DVec3::new(0.05, 0.3, 0.8), DVec3::splat(0.6));
+
+    let objects: Vec<Box<dyn Hittable>> = vec![
+        Box::new(Sphere::stationary(
+            DVec3::new(0.0, -1000.0, 0.0),
+            1000.0,
+            ground,
+        )),
+        Box::new(cloud),
+        Box::new(smoke),
+    ];
+
+    let world = BoundingVolumeHierarchyNode::new(objects);
+
+    let look_from = DVec3::new(0.0, 3.0, 12.0);
+    let look_at = DVec3::new(0.0, 2.0, 0.0);
+    let image = Image::from_width_aspect_ratio(400, 16.0 / 9.0, 255);
+
+    let mut camera = CameraBuilder::default()
+        .look_from(look_from)
+        .look_at(look_at)
+        .fov(30.0)
+        .samples_per_pixel(100)
+        .max_depth(50)
+        .image(image)
+        .build();
+
+    camera.render_image_with_progress(&world);
+
+    match camera.save_image("images/clouds.png") {
+        Ok(_) => println!("Image saved successfully!"),
+        Err(error) => println!("Failed to save the image: {error}"),
+    }
+}
@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use rust_tracer::prelude::*;
+
+fn main() {
+    let red = Arc::new(Lambertian::with_solid(DVec3::new(0.65, 0.05, 0.05)));
+    let white = Arc::new(Lambertian::with_solid(DVec3::splat(0.73)));
+    let green = Arc::new(Lambertian::with_solid(DVec3::new(0.12, 0.45, 0.15)));
+    let light = Arc::new(DiffuseLight::with_solid(DVec3::splat(7.0)));
+
+    let tall_box = Transform::from_scale_rotation_translation(
+        Arc::new(Cuboid::new(
+            DVec3::ZERO,
+            DVec3::new(165.0, 330.0, 165.0),
+            white.clone(),
+        )),
+        DVec3::ONE,
+        DVec3::Y,
+        15.0,
+        DVec3::new(265.0, 0.0, 295.0),
+    );
+    let short_box = Transform::from_scale_rotation_translation(
+        Arc::new(Cuboid::new(
+            DVec3::ZERO,
+            DVec3::new(165.0, 165.0, 165.0),
+            white.clone(),
+        )),
+        DVec3::ONE,
+        DVec3::Y,
+        -18.0,
+        DVec3::new(130.0, 0.0, 65.0),
+    );
+
+    let objects: Vec<Box<dyn Hittable>> = vec![
+        Box::new(Quad::new(
+            DVec3::new(555.0, 0.0, 0.0),
+            DVec3::new(0.0, 555.0, 0.0),
+            DVec3::new(0.0, 0.0, 555.0),
+            green,
+        )),
+        Box::new(Quad::new(
+            DVec3::new(0.0, 0.0, 0.0),
+            DVec3::new(0.0, 555.0, 0.0),
+            DVec3::new(0.0, 0.0, 555.0),
+            red,
+        )),
+        Box::new(Quad::new(
+            DVec3::new(113.0, 554.0, 127.0),
+            DVec3::new(330.0, 0.0, 0.0),
+            DVec3::new(0.0, 0.0, 305.0),
+            light,
+        )),
+        Box::new(Quad::new(
+            DVec3::new(0.0, 555.0, 0.0),
+            DVec3::new(555.0, 0.0, 0.0),
+            DVec3::new(0.0, 0.0, 555.0),
+            white.clone(),
+        )),
+        Box::new(Quad::new(
+            DVec3::new(0.0, 0.0, 0.0),
+            DVec3::new(555.0, 0.0, 0.0),
+            DVec3::new(0.0, 0.0, 555.0),
+            white.clone(),
+        )),
+        Box::new(Quad::new(
+            DVec3::new(0.0, 0.0, 555.0),
+            DVec3::new(555.0, 0.0, 0.0),
+            DVec3::new(0.0, 555.0, 0.0),
+            white,
+        )),
+        Box::new(ConstantMedium::with_solid(
+            Arc::new(tall_box),
+            0.01,
+            DVec3::ZERO,
+        )),
+        Box::new(ConstantMedium::with_solid(
+            Arc::new(short_box),
+            0.01,
+            DVec3::ONE,
+        )),
+    ];
+
+    let world = BoundingVolumeHierarchyNode::new(objects);
+
+    let lights = Quad::new(
+        DVec3::new(113.0, 554.0, 127.0),
+        DVec3::new(330.0, 0.0, 0.0),
+        DVec3::new(0.0, 0.0, 305.0),
+        Arc::new(DiffuseLight::with_solid(DVec3::ZERO)),
+    );
+
+    let look_from = DVec3::new(278.0, 278.0, -800.0);
+    let look_at = DVec3::new(278.0, 278.0, 0.0);
+    let image = Image::from_width_aspect_ratio(600, 1.0, 255);
+
+    let mut camera = CameraBuilder::default()
+        .look_from(look_from)
+        .look_at(look_at)
+        .fov(40.0)
+        .samples_per_pixel(200)
+        .background(Background::Solid(DVec3::ZERO))
+        .lights(lights)
+        .image(image)
+        .build();
+
+    camera.render_image_with_progress(&world);
+
+    match camera.save_image("images/cornell_smoke.png") {
+        Ok(_) => println!("Image saved successfully!"),
+        Err(error) => println!("Failed to save the image: {error}"),
+    }
+}
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use rust_tracer::prelude::*;
+
+fn main() {
+    let floor = Arc::new(Lambertian::with_solid(DVec3::splat(0.8)));
+    let light = Arc::new(DiffuseLight::with_solid(DVec3::splat(40.0)));
+
+    // Flint glass with its dispersion exaggerated, so the rainbow edges stand out.
+    let flint = Arc::new(Dielectric::from_dispersion(Dispersion::Cauchy {
+        a: 1.6,
+        b: 0.05,
+    }));
+    let diamond = Arc::new(Dielectric::from_dispersion(Dispersion::DIAMOND));
+
+    let objects: Vec<Box<dyn Hittable>> = vec![
+        Box::new(Quad::new(
+            DVec3::new(-20.0, 0.0, -20.0),
+            DVec3::new(40.0, 0.0, 0.0),
+            DVec3::new(0.0, 0.0, 40.0),
+            floor,
+        )),
+        Box::new(Sphere::stationary(DVec3::new(-1.2, 1.0, 0.0), 1.0, flint)),
+        Box::new(Sphere::stationary(DVec3::new(1.2, 1.0, 0.0), 1.0, diamond)),
+        Box::new(Sphere::stationary(
+            DVec3::new(0.0, 8.0, -6.0),
+            1.0,
+            light.clone(),
+        )),
+    ];
+
+    let world = BoundingVolumeHierarchyNode::new(objects);
+
+    let look_from = DVec3::new(0.0, 3.0, 8.0);
+    let look_at = DVec3::new(0.0, 0.8, 0.0);
+    let image = Image::from_width_aspect_ratio(400, 16.0 / 9.0, 255);
+
+    let mut camera = CameraBuilder::default()
+        .look_from(look_from)
+        .look_at(look_at)
+        .fov(35.0)
+        .samples_per_pixel(400)
+        .background(Background::Solid(DVec3::splat(0.02)))
+        .lights(Sphere::stationary(DVec3::new(0.0, 8.0, -6.0), 1.0, light))
+        .spectral(SpectralMode::Dispersion)
+        .tone_map(ToneMapOperator::AcesFilmic)
+        .image(image)
+        .build();
+
+    camera.render_image_with_progress(&world);
+
+    match camera.save_image("images/dispersion.png") {
+        Ok(_) => println!("Image saved successfully!"),
+        Err(error) => println!("Failed to save the image: {error}"),
+    }
+}
@@ -62,15 +62,15 @@ fn main() {
 
     distant_camera.render_image_with_progress(&world);
 
-    match distant_camera.save_image("distant_view") {
+    match distant_camera.save_image("images/distant_view.png") {
         Ok(_) => println!("Image saved successfully!"),
-        Err(_) => println!("Failed to save the image!"),
+        Err(error) => println!("Failed to save the image: {error}"),
     }
 
     zoom_camera.render_image_with_progress(&world);
 
-    match zoom_camera.save_image("zoom_view") {
+    match zoom_camera.save_image("images/zoom_view.png") {
         Ok(_) => println!("Image saved successfully!"),
-        Err(_) => println!("Failed to save the image!"),
+        Err(error) => println!("Failed to save the image: {error}"),
     }
 }
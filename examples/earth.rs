@@ -20,8 +20,8 @@ fn main() {
 
     camera.render_image_with_progress(&earth_sphere);
 
-    match camera.save_image("earth") {
+    match camera.save_image("images/earth.png") {
         Ok(_) => println!("Image saved successfully!"),
-        Err(_) => println!("Failed to save the image!"),
+        Err(error) => println!("Failed to save the image: {error}"),
     }
 }
@@ -0,0 +1,49 @@
+use std::{path::Path, sync::Arc};
+
+use rust_tracer::prelude::*;
+
+fn main() {
+    let environment = match EnvironmentMap::open(Path::new("assets/earthmap.jpg")) {
+        Ok(environment) => environment,
+        Err(error) => {
+            eprintln!("Failed to load the environment map: {error}");
+            return;
+        }
+    };
+
+    let material_metal = Arc::new(Metal::new(DVec3::new(0.8, 0.8, 0.8), 0.0));
+    let material_glass = Arc::new(Dielectric::new(1.5));
+
+    let objects: Vec<Box<dyn Hittable>> = vec![
+        Box::new(Sphere::stationary(
+            DVec3::new(-1.1, 0.0, 0.0),
+            1.0,
+            material_metal,
+        )),
+        Box::new(Sphere::stationary(
+            DVec3::new(1.1, 0.0, 0.0),
+            1.0,
+            material_glass,
+        )),
+    ];
+
+    let world = BoundingVolumeHierarchyNode::new(objects);
+
+    let look_from = DVec3::new(0.0, 1.0, 6.0);
+    let image = Image::from_width_aspect_ratio(400, 16.0 / 9.0, 255);
+
+    let mut camera = CameraBuilder::default()
+        .look_from(look_from)
+        .look_at(DVec3::ZERO)
+        .fov(40.0)
+        .background(Background::Environment(environment))
+        .image(image)
+        .build();
+
+    camera.render_image_with_progress(&world);
+
+    match camera.save_image("images/environment_map.png") {
+        Ok(_) => println!("Image saved successfully!"),
+        Err(error) => println!("Failed to save the image: {error}"),
+    }
+}
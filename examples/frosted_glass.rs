@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use rust_tracer::prelude::*;
+
+fn main() {
+    let checker = Arc::new(Lambertian::new(Texture::Checker(Checker::with_solid(
+        0.5,
+        DVec3::new(0.8, 0.1, 0.1),
+        DVec3::splat(0.9),
+    ))));
+    let light = Arc::new(DiffuseLight::with_solid(DVec3::splat(8.0)));
+
+    let mut objects: Vec<Box<dyn Hittable>> = vec![
+        Box::new(Quad::new(
+            DVec3::new(-20.0, 0.0, -20.0),
+            DVec3::new(40.0, 0.0, 0.0),
+            DVec3::new(0.0, 0.0, 40.0),
+            checker.clone(),
+        )),
+        Box::new(Quad::new(
+            DVec3::new(-20.0, 0.0, -3.0),
+            DVec3::new(40.0, 0.0, 0.0),
+            DVec3::new(0.0, 20.0, 0.0),
+            checker,
+        )),
+        Box::new(Quad::new(
+            DVec3::new(-2.0, 6.0, 0.0),
+            DVec3::new(4.0, 0.0, 0.0),
+            DVec3::new(0.0, 0.0, 2.0),
+            light.clone(),
+        )),
+    ];
+
+    // From clear to heavily frosted.
+    for (index, roughness) in [0.0, 0.1, 0.3, 0.6].into_iter().enumerate() {
+        objects.push(Box::new(Sphere::stationary(
+            DVec3::new(-3.3 + 2.2 * index as f64, 1.0, 0.0),
+            1.0,
+            Arc::new(RoughDielectric::with_roughness(1.5, roughness)),
+        )));
+    }
+
+    let world = BoundingVolumeHierarchyNode::new(objects);
+
+    let lights = Quad::new(
+        DVec3::new(-2.0, 6.0, 0.0),
+        DVec3::new(4.0, 0.0, 0.0),
+        DVec3::new(0.0, 0.0, 2.0),
+        light,
+    );
+
+    let look_from = DVec3::new(0.0, 2.0, 9.0);
+    let look_at = DVec3::new(0.0, 1.0, 0.0);
+    let image = Image::from_width_aspect_ratio(600, 16.0 / 9.0, 255);
+
+    let mut camera = CameraBuilder::default()
+        .look_from(look_from)
+        .look_at(look_at)
+        .fov(40.0)
+        .samples_per_pixel(200)
+        .background(Background::Solid(DVec3::splat(0.1)))
+        .lights(lights)
+        .tone_map(ToneMapOperator::AcesFilmic)
+        .image(image)
+        .build();
+
+    camera.render_image_with_progress(&world);
+
+    match camera.save_image("images/frosted_glass.png") {
+        Ok(_) => println!("Image saved successfully!"),
+        Err(error) => println!("Failed to save the image: {error}"),
+    }
+}
@@ -91,8 +91,8 @@ fn main() {
 
     camera.render_image_with_progress(&world);
 
-    match camera.save_image("many_spheres") {
+    match camera.save_image("images/many_spheres.png") {
         Ok(_) => println!("Image saved successfully!"),
-        Err(_) => println!("Failed to save the image!"),
+        Err(error) => println!("Failed to save the image: {error}"),
     }
 }
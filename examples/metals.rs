@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use rust_tracer::prelude::*;
+
+fn main() {
+    let ground = Arc::new(Lambertian::new(Texture::Checker(Checker::with_solid(
+        0.5,
+        DVec3::splat(0.2),
+        DVec3::splat(0.8),
+    ))));
+    let light = Arc::new(DiffuseLight::with_solid(DVec3::splat(10.0)));
+
+    // The copper sphere switches between polished and brushed in a checker pattern.
+    let patchy_roughness = Texture::Checker(Checker::with_solid(
+        0.3,
+        DVec3::splat(0.05),
+        DVec3::splat(0.5),
+    ));
+
+    let objects: Vec<Box<dyn Hittable>> = vec![
+        Box::new(Sphere::stationary(
+            DVec3::new(0.0, -1000.0, 0.0),
+            1000.0,
+            ground,
+        )),
+        Box::new(Sphere::stationary(
+            DVec3::new(-3.3, 1.0, 0.0),
+            1.0,
+            Arc::new(Conductor::with_roughness(ComplexIor::GOLD, 0.2)),
+        )),
+        Box::new(Sphere::stationary(
+            DVec3::new(-1.1, 1.0, 0.0),
+            1.0,
+            Arc::new(Conductor::new(ComplexIor::COPPER, patchy_roughness)),
+        )),
+        Box::new(Sphere::stationary(
+            DVec3::new(1.1, 1.0, 0.0),
+            1.0,
+            Arc::new(Conductor::with_roughness(ComplexIor::ALUMINIUM, 0.4)),
+        )),
+        Box::new(Sphere::stationary(
+            DVec3::new(3.3, 1.0, 0.0),
+            1.0,
+            Arc::new(Conductor::with_roughness(ComplexIor::SILVER, 0.0)),
+        )),
+        Box::new(Quad::new(
+            DVec3::new(-2.0, 6.0, -1.0),
+            DVec3::new(4.0, 0.0, 0.0),
+            DVec3::new(0.0, 0.0, 2.0),
+            light.clone(),
+        )),
+    ];
+
+    let world = BoundingVolumeHierarchyNode::new(objects);
+
+    let lights = Quad::new(
+        DVec3::new(-2.0, 6.0, -1.0),
+        DVec3::new(4.0, 0.0, 0.0),
+        DVec3::new(0.0, 0.0, 2.0),
+        light,
+    );
+
+    let look_from = DVec3::new(0.0, 2.5, 9.0);
+    let look_at = DVec3::new(0.0, 1.0, 0.0);
+    let image = Image::from_width_aspect_ratio(600, 16.0 / 9.0, 255);
+
+    let mut camera = CameraBuilder::default()
+        .look_from(look_from)
+        .look_at(look_at)
+        .fov(40.0)
+        .samples_per_pixel(100)
+        .background(Background::Gradient {
+            bottom: DVec3::splat(0.3),
+            top: DVec3::new(0.15, 0.2, 0.3),
+        })
+        .lights(lights)
+        .tone_map(ToneMapOperator::AcesFilmic)
+        .image(image)
+        .build();
+
+    camera.render_image_with_progress(&world);
+
+    match camera.save_image("images/metals.png") {
+        Ok(_) => println!("Image saved successfully!"),
+        Err(error) => println!("Failed to save the image: {error}"),
+    }
+}
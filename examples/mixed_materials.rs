@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use rust_tracer::{material::Material, prelude::*};
+
+fn main() {
+    let ground = Arc::new(Lambertian::new(Texture::Checker(Checker::with_solid(
+        0.5,
+        DVec3::splat(0.2),
+        DVec3::splat(0.8),
+    ))));
+    let light = Arc::new(DiffuseLight::with_solid(DVec3::splat(10.0)));
+
+    let materials: [Arc<dyn Material>; 4] = [
+        // Paint chipped off a mirror in a checker pattern.
+        Arc::new(MixMaterial::new(
+            Arc::new(Lambertian::with_solid(DVec3::new(0.8, 0.1, 0.1))),
+            Arc::new(Metal::new(DVec3::splat(0.9), 0.0)),
+            Texture::Checker(Checker::with_solid(0.2, DVec3::ZERO, DVec3::ONE)),
+        )),
+        // Copper with patches of rust.
+        Arc::new(MixMaterial::new(
+            Arc::new(Conductor::with_roughness(ComplexIor::COPPER, 0.3)),
+            Arc::new(Lambertian::with_solid(DVec3::new(0.35, 0.15, 0.05))),
+            Texture::Perlin(PerlinTexture::new_scaled(4.0)),
+        )),
+        // Varnished parquet.
+        Arc::new(Layered::new(
+            Arc::new(Lambertian::new(Texture::Checker(Checker::with_solid(
+                0.15,
+                DVec3::new(0.45, 0.25, 0.1),
+                DVec3::new(0.3, 0.15, 0.05),
+            )))),
+            1.5,
+        )),
+        // Glazed ceramic.
+        Arc::new(Layered::new(
+            Arc::new(Lambertian::with_solid(DVec3::new(0.2, 0.5, 0.4))),
+            1.5,
+        )),
+    ];
+
+    let mut objects: Vec<Box<dyn Hittable>> = vec![Box::new(Sphere::stationary(
+        DVec3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        ground,
+    ))];
+
+    for (index, material) in materials.into_iter().enumerate() {
+        objects.push(Box::new(Sphere::stationary(
+            DVec3::new(-3.3 + 2.2 * index as f64, 1.0, 0.0),
+            1.0,
+            material,
+        )));
+    }
+
+    objects.push(Box::new(Quad::new(
+        DVec3::new(-2.0, 6.0, -1.0),
+        DVec3::new(4.0, 0.0, 0.0),
+        DVec3::new(0.0, 0.0, 2.0),
+        light.clone(),
+    )));
+
+    let world = BoundingVolumeHierarchyNode::new(objects);
+
+    let lights = Quad::new(
+        DVec3::new(-2.0, 6.0, -1.0),
+        DVec3::new(4.0, 0.0, 0.0),
+        DVec3::new(0.0, 0.0, 2.0),
+        light,
+    );
+
+    let look_from = DVec3::new(0.0, 2.5, 9.0);
+    let look_at = DVec3::new(0.0, 1.0, 0.0);
+    let image = Image::from_width_aspect_ratio(600, 16.0 / 9.0, 255);
+
+    let mut camera = CameraBuilder::default()
+        .look_from(look_from)
+        .look_at(look_at)
+        .fov(40.0)
+        .samples_per_pixel(100)
+        .background(Background::Gradient {
+            bottom: DVec3::splat(0.3),
+            top: DVec3::new(0.15, 0.2, 0.3),
+        })
+        .lights(lights)
+        .tone_map(ToneMapOperator::AcesFilmic)
+        .image(image)
+        .build();
+
+    camera.render_image_with_progress(&world);
+
+    match camera.save_image("images/mixed_materials.png") {
+        Ok(_) => println!("Image saved successfully!"),
+        Err(error) => println!("Failed to save the image: {error}"),
+    }
+}
@@ -0,0 +1,51 @@
+use std::{env, path::PathBuf, sync::Arc};
+
+use rust_tracer::{obj::load_obj, prelude::*};
+
+fn main() {
+    let path = env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("assets/pyramid.obj"));
+
+    let meshes = match load_obj(&path) {
+        Ok(meshes) => meshes,
+        Err(error) => {
+            eprintln!("Failed to load the model: {error}");
+            return;
+        }
+    };
+
+    let ground_material = Arc::new(Lambertian::with_solid(DVec3::new(0.5, 0.5, 0.5)));
+
+    let mut objects: Vec<Box<dyn Hittable>> = vec![Box::new(Sphere::stationary(
+        DVec3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        ground_material,
+    ))];
+    objects.extend(
+        meshes
+            .into_iter()
+            .map(|mesh| Box::new(mesh) as Box<dyn Hittable>),
+    );
+
+    let world = BoundingVolumeHierarchyNode::new(objects);
+
+    let look_from = DVec3::new(3.0, 2.5, 5.0);
+    let look_at = DVec3::new(0.0, 0.5, 0.0);
+    let image = Image::from_width_aspect_ratio(400, 16.0 / 9.0, 255);
+
+    let mut camera = CameraBuilder::default()
+        .look_from(look_from)
+        .look_at(look_at)
+        .fov(40.0)
+        .image(image)
+        .build();
+
+    camera.render_image_with_progress(&world);
+
+    match camera.save_image("images/obj_model.png") {
+        Ok(_) => println!("Image saved successfully!"),
+        Err(error) => println!("Failed to save the image: {error}"),
+    }
+}
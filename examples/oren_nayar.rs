@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use rust_tracer::{material::Material, prelude::*};
+
+fn main() {
+    let ground = Arc::new(Lambertian::new(Texture::Checker(Checker::with_solid(
+        0.5,
+        DVec3::splat(0.2),
+        DVec3::splat(0.8),
+    ))));
+    let light = Arc::new(DiffuseLight::with_solid(DVec3::splat(10.0)));
+
+    // Lit from behind the camera the rough sphere looks flat like the full moon, while the
+    // Lambertian one darkens towards its rim.
+    let materials: [Arc<dyn Material>; 2] = [
+        Arc::new(Lambertian::with_solid(DVec3::splat(0.6))),
+        Arc::new(OrenNayar::with_solid(DVec3::splat(0.6), 40.0)),
+    ];
+
+    let mut objects: Vec<Box<dyn Hittable>> = vec![Box::new(Sphere::stationary(
+        DVec3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        ground,
+    ))];
+
+    for (index, material) in materials.into_iter().enumerate() {
+        objects.push(Box::new(Sphere::stationary(
+            DVec3::new(-1.1 + 2.2 * index as f64, 1.0, 0.0),
+            1.0,
+            material,
+        )));
+    }
+
+    objects.push(Box::new(Quad::new(
+        DVec3::new(-2.0, 1.0, 12.0),
+        DVec3::new(4.0, 0.0, 0.0),
+        DVec3::new(0.0, 4.0, 0.0),
+        light.clone(),
+    )));
+
+    let world = BoundingVolumeHierarchyNode::new(objects);
+
+    let lights = Quad::new(
+        DVec3::new(-2.0, 1.0, 12.0),
+        DVec3::new(4.0, 0.0, 0.0),
+        DVec3::new(0.0, 4.0, 0.0),
+        light,
+    );
+
+    let look_from = DVec3::new(0.0, 2.5, 9.0);
+    let look_at = DVec3::new(0.0, 1.0, 0.0);
+    let image = Image::from_width_aspect_ratio(600, 16.0 / 9.0, 255);
+
+    let mut camera = CameraBuilder::default()
+        .look_from(look_from)
+        .look_at(look_at)
+        .fov(40.0)
+        .samples_per_pixel(100)
+        .background(Background::Solid(DVec3::ZERO))
+        .lights(lights)
+        .tone_map(ToneMapOperator::AcesFilmic)
+        .image(image)
+        .build();
+
+    camera.render_image_with_progress(&world);
+
+    match camera.save_image("images/oren_nayar.png") {
+        Ok(_) => println!("Image saved successfully!"),
+        Err(error) => println!("Failed to save the image: {error}"),
+    }
+}
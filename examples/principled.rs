@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use rust_tracer::prelude::*;
+
+fn main() {
+    let ground = Arc::new(Lambertian::new(Texture::Checker(Checker::with_solid(
+        0.5,
+        DVec3::splat(0.2),
+        DVec3::splat(0.8),
+    ))));
+    let light = Arc::new(DiffuseLight::with_solid(DVec3::splat(10.0)));
+
+    // Roughness driven by a texture, polished stripes on a brushed metal.
+    let patchy_roughness = Texture::Checker(Checker::with_solid(
+        0.3,
+        DVec3::splat(0.1),
+        DVec3::splat(0.5),
+    ));
+
+    let materials = [
+        // Plastic.
+        Principled::with_solid(DVec3::new(0.8, 0.1, 0.1)).roughness(0.3),
+        // Brushed brass.
+        Principled::with_solid(DVec3::new(0.9, 0.7, 0.3))
+            .metallic(1.0)
+            .roughness_texture(patchy_roughness),
+        // Car paint: a metallic flake base under a clearcoat.
+        Principled::with_solid(DVec3::new(0.1, 0.2, 0.6))
+            .metallic(0.5)
+            .roughness(0.6)
+            .clearcoat(1.0),
+        // Velvet.
+        Principled::with_solid(DVec3::new(0.3, 0.05, 0.4))
+            .roughness(1.0)
+            .specular(0.0)
+            .sheen(1.0)
+            .sheen_tint(1.0),
+        // Tinted frosted glass.
+        Principled::with_solid(DVec3::new(0.6, 0.9, 0.7))
+            .roughness(0.2)
+            .transmission(1.0),
+    ];
+
+    let mut objects: Vec<Box<dyn Hittable>> = vec![Box::new(Sphere::stationary(
+        DVec3::new(0.0, -1000.0, 0.0),
+        1000.0,
+        ground,
+    ))];
+
+    for (index, material) in materials.into_iter().enumerate() {
+        objects.push(Box::new(Sphere::stationary(
+            DVec3::new(-4.4 + 2.2 * index as f64, 1.0, 0.0),
+            1.0,
+            Arc::new(material),
+        )));
+    }
+
+    objects.push(Box::new(Quad::new(
+        DVec3::new(-2.0, 6.0, -1.0),
+        DVec3::new(4.0, 0.0, 0.0),
+        DVec3::new(0.0, 0.0, 2.0),
+        light.clone(),
+    )));
+
+    let world = BoundingVolumeHierarchyNode::new(objects);
+
+    let lights = Quad::new(
+        DVec3::new(-2.0, 6.0, -1.0),
+        DVec3::new(4.0, 0.0, 0.0),
+        DVec3::new(0.0, 0.0, 2.0),
+        light,
+    );
+
+    let look_from = DVec3::new(0.0, 2.5, 11.0);
+    let look_at = DVec3::new(0.0, 1.0, 0.0);
+    let image = Image::from_width_aspect_ratio(600, 16.0 / 9.0, 255);
+
+    let mut camera = CameraBuilder::default()
+        .look_from(look_from)
+        .look_at(look_at)
+        .fov(40.0)
+        .samples_per_pixel(100)
+        .background(Background::Gradient {
+            bottom: DVec3::splat(0.3),
+            top: DVec3::new(0.15, 0.2, 0.3),
+        })
+        .lights(lights)
+        .tone_map(ToneMapOperator::AcesFilmic)
+        .image(image)
+        .build();
+
+    camera.render_image_with_progress(&world);
+
+    match camera.save_image("images/principled.png") {
+        Ok(_) => println!("Image saved successfully!"),
+        Err(error) => println!("Failed to save the image: {error}"),
+    }
+}
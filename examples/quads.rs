@@ -0,0 +1,63 @@
+use std::sync::Arc;
+
+use rust_tracer::prelude::*;
+
+fn main() {
+    let left_red = Arc::new(Lambertian::with_solid(DVec3::new(1.0, 0.2, 0.2)));
+    let back_green = Arc::new(Lambertian::with_solid(DVec3::new(0.2, 1.0, 0.2)));
+    let right_blue = Arc::new(Lambertian::with_solid(DVec3::new(0.2, 0.2, 1.0)));
+    let upper_orange = Arc::new(Lambertian::with_solid(DVec3::new(1.0, 0.5, 0.0)));
+    let lower_teal = Arc::new(Lambertian::with_solid(DVec3::new(0.2, 0.8, 0.8)));
+
+    let objects: Vec<Box<dyn Hittable>> = vec![
+        Box::new(Quad::new(
+            DVec3::new(-3.0, -2.0, 5.0),
+            DVec3::new(0.0, 0.0, -4.0),
+            DVec3::new(0.0, 4.0, 0.0),
+            left_red,
+        )),
+        Box::new(Quad::new(
+            DVec3::new(-2.0, -2.0, 0.0),
+            DVec3::new(4.0, 0.0, 0.0),
+            DVec3::new(0.0, 4.0, 0.0),
+            back_green,
+        )),
+        Box::new(Quad::new(
+            DVec3::new(3.0, -2.0, 1.0),
+            DVec3::new(0.0, 0.0, 4.0),
+            DVec3::new(0.0, 4.0, 0.0),
+            right_blue,
+        )),
+        Box::new(Quad::new(
+            DVec3::new(-2.0, 3.0, 1.0),
+            DVec3::new(4.0, 0.0, 0.0),
+            DVec3::new(0.0, 0.0, 4.0),
+            upper_orange,
+        )),
+        Box::new(Quad::new(
+            DVec3::new(-2.0, -3.0, 5.0),
+            DVec3::new(4.0, 0.0, 0.0),
+            DVec3::new(0.0, 0.0, -4.0),
+            lower_teal,
+        )),
+    ];
+
+    let world = BoundingVolumeHierarchyNode::new(objects);
+
+    let look_from = DVec3::new(0.0, 0.0, 9.0);
+    let image = Image::from_width_aspect_ratio(400, 1.0, 255);
+
+    let mut camera = CameraBuilder::default()
+        .fov(80.0)
+        .look_from(look_from)
+        .look_at(DVec3::ZERO)
+        .image(image)
+        .build();
+
+    camera.render_image_with_progress(&world);
+
+    match camera.save_image("images/quads.png") {
+        Ok(_) => println!("Image saved successfully!"),
+        Err(error) => println!("Failed to save the image: {error}"),
+    }
+}
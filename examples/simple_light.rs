@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use rust_tracer::prelude::*;
+
+fn main() {
+    let perlin_material = Arc::new(Lambertian::new(Texture::Perlin(PerlinTexture::new_scaled(
+        4.0,
+    ))));
+    let light_material = Arc::new(DiffuseLight::with_solid(DVec3::splat(4.0)));
+
+    let objects: Vec<Box<dyn Hittable>> = vec![
+        Box::new(Sphere::stationary(
+            DVec3::new(0.0, -1000.0, 0.0),
+            1000.0,
+            perlin_material.clone(),
+        )),
+        Box::new(Sphere::stationary(
+            DVec3::new(0.0, 2.0, 0.0),
+            2.0,
+            perlin_material,
+        )),
+        Box::new(Sphere::stationary(
+            DVec3::new(0.0, 7.0, 0.0),
+            2.0,
+            light_material.clone(),
+        )),
+        Box::new(Quad::new(
+            DVec3::new(3.0, 1.0, -2.0),
+            DVec3::new(2.0, 0.0, 0.0),
+            DVec3::new(0.0, 2.0, 0.0),
+            light_material,
+        )),
+    ];
+
+    let world = BoundingVolumeHierarchyNode::new(objects);
+
+    let look_from = DVec3::new(26.0, 3.0, 6.0);
+    let look_at = DVec3::new(0.0, 2.0, 0.0);
+    let image = Image::from_width_aspect_ratio(400, 16.0 / 9.0, 255);
+
+    let mut camera = CameraBuilder::default()
+        .look_from(look_from)
+        .look_at(look_at)
+        .fov(20.0)
+        .background(Background::Solid(DVec3::ZERO))
+        .tone_map(ToneMapOperator::AcesFilmic)
+        .image(image)
+        .build();
+
+    camera.render_image_with_progress(&world);
+
+    match camera.save_image("images/simple_light.png") {
+        Ok(_) => println!("Image saved successfully!"),
+        Err(error) => println!("Failed to save the image: {error}"),
+    }
+}
@@ -29,8 +29,8 @@ fn main() {
 
     camera.render_image_with_progress(&hittable_list);
 
-    match camera.save_image("two_perlin_spheres") {
+    match camera.save_image("images/two_perlin_spheres.png") {
         Ok(_) => println!("Image saved successfully!"),
-        Err(_) => println!("Failed to save the image!"),
+        Err(error) => println!("Failed to save the image: {error}"),
     }
 }
@@ -0,0 +1,158 @@
+use std::{f64::consts::PI, path::Path};
+
+use glam::{DVec2, DVec3};
+use image::{io::Reader as ImageReader, ImageResult, Rgb32FImage};
+
+use crate::{color::TransferFunction, spectrum::SampledWavelengths};
+
+/// What a ray sees when it leaves the scene without hitting anything.
+#[derive(Debug)]
+pub enum Background {
+    Solid(DVec3),
+    Gradient { bottom: DVec3, top: DVec3 },
+    Environment(EnvironmentMap),
+}
+
+impl Default for Background {
+    fn default() -> Self {
+        Background::Gradient {
+            bottom: DVec3::new(1.0, 1.0, 1.0),
+            top: DVec3::new(0.5, 0.7, 1.0),
+        }
+    }
+}
+
+impl Background {
+    /// The radiance arriving from `direction`, upsampled as an illuminant for spectral rays.
+    pub fn color(&self, direction: DVec3, wavelengths: SampledWavelengths) -> DVec3 {
+        wavelengths.upsample_illuminant(self.rgb(direction))
+    }
+
+    fn rgb(&self, direction: DVec3) -> DVec3 {
+        match self {
+            Background::Solid(color) => *color,
+            Background::Gradient { bottom, top } => {
+                let unit_direction = direction.normalize();
+                let a = 0.5 * (unit_direction.y + 1.0);
+
+                (1.0 - a) * *bottom + a * *top
+            }
+            Background::Environment(environment) => environment.sample(direction),
+        }
+    }
+}
+
+/// An equirectangular (latitude/longitude) image surrounding the scene.
+///
+/// High dynamic range images such as Radiance `.hdr` or OpenEXR are used as linear radiance,
+/// 8 bit images are decoded from sRGB.
+#[derive(Debug)]
+pub struct EnvironmentMap {
+    data: Rgb32FImage,
+    intensity: f64,
+}
+
+impl EnvironmentMap {
+    pub fn open(file: &Path) -> ImageResult<Self> {
+        let image = ImageReader::open(file)?.decode()?;
+        let high_dynamic_range = matches!(
+            image.color(),
+            image::ColorType::Rgb32F | image::ColorType::Rgba32F
+        );
+
+        let mut data = image.into_rgb32f();
+
+        if !high_dynamic_range {
+            data.pixels_mut().for_each(|pixel| {
+                let color = DVec3::from_array(pixel.0.map(f64::from));
+                pixel.0 = TransferFunction::Srgb.decode(color).as_vec3().to_array();
+            });
+        }
+
+        Ok(Self {
+            data,
+            intensity: 1.0,
+        })
+    }
+
+    pub fn with_intensity(mut self, intensity: f64) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    fn sample(&self, direction: DVec3) -> DVec3 {
+        let DVec2 { x: u, y: v } = direction_to_uv(direction);
+
+        let x = ((u * self.data.width() as f64) as u32).min(self.data.width() - 1);
+        let y = (((1.0 - v) * self.data.height() as f64) as u32).min(self.data.height() - 1);
+
+        let pixel = self.data.get_pixel(x, y);
+
+        DVec3::new(pixel.0[0] as f64, pixel.0[1] as f64, pixel.0[2] as f64) * self.intensity
+    }
+}
+
+/// `u` goes around the Y axis from -X through +Z, +X and -Z back to -X, `v` from -Y up to +Y.
+fn direction_to_uv(direction: DVec3) -> DVec2 {
+    let unit_direction = direction.normalize();
+    let theta = (-unit_direction.y).acos();
+    let phi = (-unit_direction.z).atan2(unit_direction.x) + PI;
+
+    DVec2::new(phi / (2.0 * PI), theta / PI)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gradient() {
+        let (bottom, top) = (DVec3::new(1.0, 0.5, 0.0), DVec3::new(0.0, 0.5, 1.0));
+        let gradient = Background::Gradient { bottom, top };
+
+        assert_eq!(gradient.rgb(DVec3::NEG_Y * 2.0), bottom);
+        assert_eq!(gradient.rgb(DVec3::Y), top);
+        assert_eq!(gradient.rgb(DVec3::X), DVec3::splat(0.5));
+
+        let solid = Background::Solid(top);
+        assert_eq!(solid.rgb(DVec3::NEG_Y), top);
+        assert_eq!(solid.rgb(DVec3::new(1.0, 2.0, 3.0)), top);
+    }
+
+    #[test]
+    fn environment_map() {
+        let uv = direction_to_uv;
+
+        // The poles.
+        assert_eq!(uv(DVec3::Y).y, 1.0);
+        assert_eq!(uv(DVec3::NEG_Y).y, 0.0);
+
+        // Around the horizon.
+        assert_eq!(uv(DVec3::Z), DVec2::new(0.25, 0.5));
+        assert_eq!(uv(DVec3::X), DVec2::new(0.5, 0.5));
+        assert_eq!(uv(DVec3::NEG_Z), DVec2::new(0.75, 0.5));
+
+        // The seam at -X, approached from both sides.
+        assert!(uv(DVec3::new(-1.0, 0.0, 1e-9)).x < 1e-6);
+        assert!(uv(DVec3::new(-1.0, 0.0, -1e-9)).x > 1.0 - 1e-6);
+
+        // Every pixel holds its own coordinates, the top row is at +Y.
+        let map = EnvironmentMap {
+            data: Rgb32FImage::from_fn(4, 2, |x, y| image::Rgb([x as f32, y as f32, 0.0])),
+            intensity: 2.0,
+        };
+        let pixel = |direction| map.sample(direction) / 2.0;
+
+        assert_eq!(pixel(DVec3::Y), DVec3::new(2.0, 0.0, 0.0));
+        assert_eq!(pixel(DVec3::NEG_Y), DVec3::new(2.0, 1.0, 0.0));
+        assert_eq!(
+            pixel(DVec3::new(-1.0, 0.1, 1e-9)),
+            DVec3::new(0.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            pixel(DVec3::new(-1.0, 0.1, -1e-9)),
+            DVec3::new(3.0, 0.0, 0.0)
+        );
+        assert_eq!(pixel(DVec3::new(1.0, -0.1, 0.0)), DVec3::new(2.0, 1.0, 0.0));
+    }
+}
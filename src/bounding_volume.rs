@@ -1,5 +1,6 @@
 use std::ops::Range;
 
+use glam::DVec3;
 use rand::Rng;
 
 use crate::{
@@ -7,79 +8,224 @@ use crate::{
     ray::Ray,
 };
 
+pub mod linear;
+
+const DEFAULT_MAX_LEAF_SIZE: usize = 4;
+const BIN_COUNT: usize = 12;
+/// Cost of visiting a node relative to intersecting one primitive.
+const TRAVERSAL_COST: f64 = 0.125;
+
+/// A bounding volume hierarchy built with a binned surface area heuristic.
+///
+/// The same objects in the same order always produce the same tree.
 #[derive(Debug)]
-pub struct BoundingVolumeHierarchyNode {
-    left: Option<Box<dyn Hittable>>,
-    right: Option<Box<dyn Hittable>>,
+pub struct BoundingVolumeHierarchyNode<T: Hittable = Box<dyn Hittable>> {
+    content: Content<T>,
     bounding_box: AxisAlignedBoundingBox,
+    /// Number of objects below this node.
+    count: usize,
+}
+
+#[derive(Debug)]
+enum Content<T: Hittable> {
+    Leaf(Vec<T>),
+    Split {
+        left: Box<BoundingVolumeHierarchyNode<T>>,
+        right: Box<BoundingVolumeHierarchyNode<T>>,
+        axis: usize,
+    },
+}
+
+#[derive(Clone, Default)]
+struct Bin {
+    count: usize,
+    bounding_box: Option<AxisAlignedBoundingBox>,
 }
 
-impl BoundingVolumeHierarchyNode {
-    pub fn new(mut objects: Vec<Box<dyn Hittable>>) -> Self {
-        let compare_function = match rand::thread_rng().gen_range(0..2) {
-            0 => AxisAlignedBoundingBox::compare_x,
-            1 => AxisAlignedBoundingBox::compare_y,
-            _ => AxisAlignedBoundingBox::compare_z,
+impl<T: Hittable> BoundingVolumeHierarchyNode<T> {
+    pub fn new(objects: Vec<T>) -> Self {
+        Self::with_max_leaf_size(objects, DEFAULT_MAX_LEAF_SIZE)
+    }
+
+    /// Builds the hierarchy, leaves hold at most `max_leaf_size` objects unless they can't be
+    /// separated because their centroids coincide.
+    pub fn with_max_leaf_size(objects: Vec<T>, max_leaf_size: usize) -> Self {
+        assert!(!objects.is_empty());
+
+        let bounding_box = enclosing_box(objects.iter().map(|object| object.bounding_box()));
+        let leaf_cost = objects.len() as f64;
+
+        let Some((axis, split, split_cost)) = Self::best_split(&objects, &bounding_box) else {
+            return if objects.len() <= max_leaf_size.max(1) {
+                Self::leaf(objects, bounding_box)
+            } else {
+                Self::split_in_half(objects, bounding_box, max_leaf_size)
+            };
         };
 
-        if objects.len() == 1 {
-            let bounding_box = objects[0].bounding_box().clone();
+        if objects.len() <= max_leaf_size.max(1) && leaf_cost <= split_cost {
+            return Self::leaf(objects, bounding_box);
+        }
+
+        let centroid_box = enclosing_centroid_box(&objects);
+        let (left, right) = objects
+            .into_iter()
+            .partition(|object| bin_index(object, &centroid_box, axis) < split);
+
+        Self::split(left, right, axis, bounding_box, max_leaf_size)
+    }
+
+    fn leaf(objects: Vec<T>, bounding_box: AxisAlignedBoundingBox) -> Self {
+        Self {
+            count: objects.len(),
+            content: Content::Leaf(objects),
+            bounding_box,
+        }
+    }
+
+    fn split(
+        left: Vec<T>,
+        right: Vec<T>,
+        axis: usize,
+        bounding_box: AxisAlignedBoundingBox,
+        max_leaf_size: usize,
+    ) -> Self {
+        Self {
+            count: left.len() + right.len(),
+            content: Content::Split {
+                left: Box::new(Self::with_max_leaf_size(left, max_leaf_size)),
+                right: Box::new(Self::with_max_leaf_size(right, max_leaf_size)),
+                axis,
+            },
+            bounding_box,
+        }
+    }
+
+    fn split_in_half(
+        mut objects: Vec<T>,
+        bounding_box: AxisAlignedBoundingBox,
+        max_leaf_size: usize,
+    ) -> Self {
+        let right = objects.split_off(objects.len() / 2);
+        let axis = bounding_box.longest_axis();
+
+        Self::split(objects, right, axis, bounding_box, max_leaf_size)
+    }
 
-            Self {
-                left: Some(objects.remove(0)),
-                right: None,
-                bounding_box,
+    /// Returns the axis, the first bin of the right side and the estimated cost of the cheapest
+    /// split, or `None` if all centroids lie in the same spot.
+    fn best_split(
+        objects: &[T],
+        bounding_box: &AxisAlignedBoundingBox,
+    ) -> Option<(usize, usize, f64)> {
+        let centroid_box = enclosing_centroid_box(objects);
+        let extent = centroid_box.max() - centroid_box.min();
+        let parent_area = bounding_box.surface_area();
+
+        let mut best: Option<(usize, usize, f64)> = None;
+
+        for axis in 0..3 {
+            if extent[axis] <= 0.0 {
+                continue;
             }
-        } else if objects.len() == 2 {
-            let first = objects.remove(0);
-            let second = objects.remove(0);
-
-            let bounding_box = AxisAlignedBoundingBox::from_boxes(
-                first.bounding_box().clone(),
-                second.bounding_box().clone(),
-            );
-
-            if compare_function(first.bounding_box(), second.bounding_box()).is_lt() {
-                Self {
-                    left: Some(first),
-                    right: Some(second),
-                    bounding_box,
-                }
-            } else {
-                Self {
-                    left: Some(second),
-                    right: Some(first),
-                    bounding_box,
-                }
+
+            let mut bins = vec![Bin::default(); BIN_COUNT];
+
+            for object in objects {
+                let bin = &mut bins[bin_index(object, &centroid_box, axis)];
+                bin.count += 1;
+                bin.bounding_box = Some(match bin.bounding_box.take() {
+                    Some(bounding_box) => AxisAlignedBoundingBox::from_boxes(
+                        bounding_box,
+                        object.bounding_box().clone(),
+                    ),
+                    None => object.bounding_box().clone(),
+                });
             }
-        } else {
-            objects
-                .sort_by(|box0, box1| compare_function(box0.bounding_box(), box1.bounding_box()));
-            let middle_index = objects.len() / 2;
 
-            let right_objects = objects.split_off(middle_index);
-            let mut left_objects = objects;
+            let left_sweep = sweep(bins.iter());
+            let mut right_sweep = sweep(bins.iter().rev());
+            right_sweep.reverse();
 
-            let left_bounding_volume = if left_objects.len() == 1 {
-                left_objects.remove(0)
-            } else {
-                Box::new(Self::new(left_objects))
-            };
-            let right_bounding_volume = Box::new(Self::new(right_objects));
-            let bounding_box = AxisAlignedBoundingBox::from_boxes(
-                left_bounding_volume.bounding_box().clone(),
-                right_bounding_volume.bounding_box.clone(),
-            );
-
-            Self {
-                left: Some(left_bounding_volume),
-                right: Some(right_bounding_volume),
-                bounding_box,
+            for split in 1..BIN_COUNT {
+                let (left_count, left_area) = left_sweep[split - 1];
+                let (right_count, right_area) = right_sweep[split];
+
+                if left_count == 0 || right_count == 0 {
+                    continue;
+                }
+
+                let cost = TRAVERSAL_COST
+                    + (left_count as f64 * left_area + right_count as f64 * right_area)
+                        / parent_area.max(f64::MIN_POSITIVE);
+
+                if best.is_none_or(|(_, _, best_cost)| cost < best_cost) {
+                    best = Some((axis, split, cost));
+                }
             }
         }
+
+        best
     }
 }
 
+/// Accumulated object count and surface area of the bins up to and including each bin.
+fn sweep<'a>(bins: impl Iterator<Item = &'a Bin>) -> Vec<(usize, f64)> {
+    let mut count = 0;
+    let mut bounding_box: Option<AxisAlignedBoundingBox> = None;
+
+    bins.map(|bin| {
+        count += bin.count;
+
+        if let Some(bin_box) = &bin.bounding_box {
+            bounding_box = Some(match bounding_box.take() {
+                Some(accumulated) => {
+                    AxisAlignedBoundingBox::from_boxes(accumulated, bin_box.clone())
+                }
+                None => bin_box.clone(),
+            });
+        }
+
+        let area = bounding_box
+            .as_ref()
+            .map_or(0.0, AxisAlignedBoundingBox::surface_area);
+
+        (count, area)
+    })
+    .collect()
+}
+
+fn bin_index<T: Hittable>(object: &T, centroid_box: &AxisAlignedBoundingBox, axis: usize) -> usize {
+    let min = centroid_box.min()[axis];
+    let extent = centroid_box.max()[axis] - min;
+    let relative = (object.bounding_box().centroid()[axis] - min) / extent;
+
+    ((relative * BIN_COUNT as f64) as usize).min(BIN_COUNT - 1)
+}
+
+fn enclosing_box<'a>(
+    mut boxes: impl Iterator<Item = &'a AxisAlignedBoundingBox>,
+) -> AxisAlignedBoundingBox {
+    let first = boxes.next().cloned().unwrap_or_default();
+
+    boxes.fold(first, |accumulated, bounding_box| {
+        AxisAlignedBoundingBox::from_boxes(accumulated, bounding_box.clone())
+    })
+}
+
+fn enclosing_centroid_box<T: Hittable>(objects: &[T]) -> AxisAlignedBoundingBox {
+    let centroids = objects
+        .iter()
+        .map(|object| object.bounding_box().centroid());
+
+    let (min, max) = centroids.fold(
+        (DVec3::INFINITY, DVec3::NEG_INFINITY),
+        |(min, max), centroid| (min.min(centroid), max.max(centroid)),
+    );
+
+    AxisAlignedBoundingBox::from_corners(min, max)
+}
+
 impl From<HittableList> for BoundingVolumeHierarchyNode {
     fn from(value: HittableList) -> Self {
         let objects = value.objects();
@@ -88,43 +234,154 @@ impl From<HittableList> for BoundingVolumeHierarchyNode {
     }
 }
 
-impl Hittable for BoundingVolumeHierarchyNode {
+/// As a light every object is equally likely to be sampled.
+impl<T: Hittable> Hittable for BoundingVolumeHierarchyNode<T> {
     fn hit(&self, ray: Ray, t_range: Range<f64>) -> Option<HitRecord> {
         if !self.bounding_box.hit(ray, t_range.clone()) {
             return None;
         }
 
-        let hit_left = if let Some(left) = &self.left {
-            left.hit(ray, t_range.clone())
-        } else {
-            None
-        };
+        match &self.content {
+            Content::Leaf(objects) => {
+                let mut closest_hit = None;
+                let mut closest_so_far = t_range.end;
 
-        let hit_right = if let Some(right) = &self.right {
-            if let Some(left) = &hit_left {
-                right.hit(ray, t_range.start..left.t)
-            } else {
-                right.hit(ray, t_range)
+                for object in objects {
+                    if let Some(hit) = object.hit(ray, t_range.start..closest_so_far) {
+                        closest_so_far = hit.t;
+                        closest_hit = Some(hit);
+                    }
+                }
+
+                closest_hit
             }
-        } else {
-            None
-        };
+            Content::Split { left, right, .. } => {
+                let hit_left = left.hit(ray, t_range.clone());
+                let closest_so_far = hit_left.as_ref().map_or(t_range.end, |hit| hit.t);
 
-        match (hit_right, hit_left) {
-            (Some(right), Some(left)) => {
-                if right.t < left.t {
-                    Some(right)
-                } else {
-                    Some(left)
-                }
+                right.hit(ray, t_range.start..closest_so_far).or(hit_left)
             }
-            (Some(right), None) => Some(right),
-            (None, Some(left)) => Some(left),
-            _ => None,
         }
     }
 
     fn bounding_box(&self) -> &AxisAlignedBoundingBox {
         &self.bounding_box
     }
+
+    fn pdf_value(&self, origin: DVec3, direction: DVec3) -> f64 {
+        if !self
+            .bounding_box
+            .hit(Ray::new(origin, direction), 0.001..f64::INFINITY)
+        {
+            return 0.0;
+        }
+
+        match &self.content {
+            Content::Leaf(objects) => {
+                objects
+                    .iter()
+                    .map(|object| object.pdf_value(origin, direction))
+                    .sum::<f64>()
+                    / self.count as f64
+            }
+            Content::Split { left, right, .. } => {
+                (left.count as f64 * left.pdf_value(origin, direction)
+                    + right.count as f64 * right.pdf_value(origin, direction))
+                    / self.count as f64
+            }
+        }
+    }
+
+    fn random(&self, origin: DVec3) -> DVec3 {
+        let index = rand::thread_rng().gen_range(0..self.count);
+
+        match &self.content {
+            Content::Leaf(objects) => objects[index].random(origin),
+            Content::Split { left, right, .. } => {
+                if index < left.count {
+                    left.random(origin)
+                } else {
+                    right.random(origin)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{material::lambertian::Lambertian, sphere::Sphere};
+
+    fn spheres() -> Vec<Sphere> {
+        let material = Arc::new(Lambertian::with_solid(DVec3::ONE));
+
+        (0..64)
+            .map(|i| {
+                let position = DVec3::new((i % 4) as f64, ((i / 4) % 4) as f64, (i / 16) as f64);
+                Sphere::stationary(position * 3.0, 1.0, material.clone())
+            })
+            .collect()
+    }
+
+    fn leaf_sizes<T: Hittable>(node: &BoundingVolumeHierarchyNode<T>) -> Vec<usize> {
+        match &node.content {
+            Content::Leaf(objects) => vec![objects.len()],
+            Content::Split { left, right, .. } => {
+                let mut sizes = leaf_sizes(left);
+                sizes.extend(leaf_sizes(right));
+                sizes
+            }
+        }
+    }
+
+    #[test]
+    fn surface_area_heuristic() {
+        let bvh = BoundingVolumeHierarchyNode::with_max_leaf_size(spheres(), 2);
+        let sizes = leaf_sizes(&bvh);
+
+        assert_eq!(sizes.iter().sum::<usize>(), 64);
+        assert!(sizes.iter().all(|&size| size <= 2));
+        assert_eq!(
+            sizes,
+            leaf_sizes(&BoundingVolumeHierarchyNode::with_max_leaf_size(
+                spheres(),
+                2
+            ))
+        );
+
+        let list = spheres();
+
+        for x in 0..4 {
+            for z in 0..4 {
+                let ray = Ray::new(
+                    DVec3::new(x as f64 * 3.0, 20.0, z as f64 * 3.0),
+                    DVec3::NEG_Y,
+                );
+                let expected = list
+                    .iter()
+                    .filter_map(|sphere| sphere.hit(ray, 0.001..f64::INFINITY))
+                    .map(|hit| hit.t)
+                    .min_by(f64::total_cmp);
+
+                assert_eq!(
+                    bvh.hit(ray, 0.001..f64::INFINITY).map(|hit| hit.t),
+                    expected
+                );
+            }
+        }
+
+        let material = Arc::new(Lambertian::with_solid(DVec3::ONE));
+        let coincident = (0..10)
+            .map(|_| Sphere::stationary(DVec3::ZERO, 1.0, material.clone()))
+            .collect();
+
+        let sizes = leaf_sizes(&BoundingVolumeHierarchyNode::with_max_leaf_size(
+            coincident, 4,
+        ));
+
+        assert!(sizes.iter().all(|&size| size <= 4));
+    }
 }
@@ -0,0 +1,324 @@
+use std::ops::Range;
+
+use glam::DVec3;
+use rand::Rng;
+
+use crate::{
+    hittable::{AxisAlignedBoundingBox, HitRecord, Hittable, HittableList},
+    ray::Ray,
+};
+
+use super::{BoundingVolumeHierarchyNode, Content};
+
+/// Nodes to visit that fit on the stack, deeper trees spill over onto the heap.
+const STACK_SIZE: usize = 64;
+
+/// A bounding volume hierarchy flattened into one contiguous array of nodes.
+///
+/// It is built with the same surface area heuristic as [`BoundingVolumeHierarchyNode`], but
+/// traversed iteratively and visits the child closer to the ray origin first.
+#[derive(Debug)]
+pub struct LinearBoundingVolumeHierarchy<T: Hittable = Box<dyn Hittable>> {
+    nodes: Vec<LinearNode>,
+    primitives: Vec<T>,
+    bounding_box: AxisAlignedBoundingBox,
+}
+
+#[derive(Debug)]
+struct LinearNode {
+    min: DVec3,
+    max: DVec3,
+    kind: NodeKind,
+}
+
+#[derive(Debug)]
+enum NodeKind {
+    /// The primitives `first..first + count`.
+    Leaf { first: usize, count: usize },
+    /// The first child directly follows its parent, the second one is at `second_child`.
+    Interior { second_child: usize, axis: usize },
+}
+
+impl<T: Hittable> LinearBoundingVolumeHierarchy<T> {
+    pub fn new(objects: Vec<T>) -> Self {
+        BoundingVolumeHierarchyNode::new(objects).into()
+    }
+
+    pub fn with_max_leaf_size(objects: Vec<T>, max_leaf_size: usize) -> Self {
+        BoundingVolumeHierarchyNode::with_max_leaf_size(objects, max_leaf_size).into()
+    }
+
+    fn flatten(
+        node: BoundingVolumeHierarchyNode<T>,
+        nodes: &mut Vec<LinearNode>,
+        primitives: &mut Vec<T>,
+    ) {
+        let min = node.bounding_box.min();
+        let max = node.bounding_box.max();
+
+        match node.content {
+            Content::Leaf(objects) => {
+                nodes.push(LinearNode {
+                    min,
+                    max,
+                    kind: NodeKind::Leaf {
+                        first: primitives.len(),
+                        count: objects.len(),
+                    },
+                });
+                primitives.extend(objects);
+            }
+            Content::Split { left, right, axis } => {
+                let index = nodes.len();
+                nodes.push(LinearNode {
+                    min,
+                    max,
+                    kind: NodeKind::Interior {
+                        second_child: 0,
+                        axis,
+                    },
+                });
+
+                Self::flatten(*left, nodes, primitives);
+
+                let second_child = nodes.len();
+                nodes[index].kind = NodeKind::Interior { second_child, axis };
+
+                Self::flatten(*right, nodes, primitives);
+            }
+        }
+    }
+}
+
+impl<T: Hittable> From<BoundingVolumeHierarchyNode<T>> for LinearBoundingVolumeHierarchy<T> {
+    fn from(value: BoundingVolumeHierarchyNode<T>) -> Self {
+        let bounding_box = value.bounding_box.clone();
+        let mut nodes = Vec::new();
+        let mut primitives = Vec::new();
+
+        Self::flatten(value, &mut nodes, &mut primitives);
+
+        Self {
+            nodes,
+            primitives,
+            bounding_box,
+        }
+    }
+}
+
+impl From<HittableList> for LinearBoundingVolumeHierarchy {
+    fn from(value: HittableList) -> Self {
+        Self::new(value.objects())
+    }
+}
+
+/// The far children still to be visited, most recent first.
+struct TraversalStack {
+    inline: [usize; STACK_SIZE],
+    size: usize,
+    overflow: Vec<usize>,
+}
+
+impl TraversalStack {
+    fn new() -> Self {
+        Self {
+            inline: [0; STACK_SIZE],
+            size: 0,
+            overflow: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, node: usize) {
+        if self.size < STACK_SIZE {
+            self.inline[self.size] = node;
+            self.size += 1;
+        } else {
+            self.overflow.push(node);
+        }
+    }
+
+    fn pop(&mut self) -> Option<usize> {
+        if let Some(node) = self.overflow.pop() {
+            return Some(node);
+        }
+
+        if self.size == 0 {
+            return None;
+        }
+
+        self.size -= 1;
+        Some(self.inline[self.size])
+    }
+}
+
+impl LinearNode {
+    fn hit(&self, origin: DVec3, inverse_direction: DVec3, t_range: &Range<f64>) -> bool {
+        let t0 = (self.min - origin) * inverse_direction;
+        let t1 = (self.max - origin) * inverse_direction;
+
+        let t_near = t0.min(t1).max_element().max(t_range.start);
+        let t_far = t0.max(t1).min_element().min(t_range.end);
+
+        t_near <= t_far
+    }
+}
+
+/// As a light every primitive is equally likely to be sampled.
+impl<T: Hittable> Hittable for LinearBoundingVolumeHierarchy<T> {
+    fn hit(&self, ray: Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let inverse_direction = ray.direction.recip();
+        let direction_is_negative = [
+            inverse_direction.x < 0.0,
+            inverse_direction.y < 0.0,
+            inverse_direction.z < 0.0,
+        ];
+
+        let mut closest_hit = None;
+        let mut closest_so_far = t_range.end;
+
+        let mut stack = TraversalStack::new();
+        let mut current = 0;
+
+        loop {
+            let node = &self.nodes[current];
+
+            if node.hit(
+                ray.origin,
+                inverse_direction,
+                &(t_range.start..closest_so_far),
+            ) {
+                match node.kind {
+                    NodeKind::Leaf { first, count } => {
+                        for primitive in &self.primitives[first..first + count] {
+                            if let Some(hit) = primitive.hit(ray, t_range.start..closest_so_far) {
+                                closest_so_far = hit.t;
+                                closest_hit = Some(hit);
+                            }
+                        }
+                    }
+                    NodeKind::Interior { second_child, axis } => {
+                        let (near, far) = if direction_is_negative[axis] {
+                            (second_child, current + 1)
+                        } else {
+                            (current + 1, second_child)
+                        };
+
+                        stack.push(far);
+                        current = near;
+                        continue;
+                    }
+                }
+            }
+
+            match stack.pop() {
+                Some(node) => current = node,
+                None => break,
+            }
+        }
+
+        closest_hit
+    }
+
+    fn bounding_box(&self) -> &AxisAlignedBoundingBox {
+        &self.bounding_box
+    }
+
+    fn pdf_value(&self, origin: DVec3, direction: DVec3) -> f64 {
+        self.primitives
+            .iter()
+            .map(|primitive| primitive.pdf_value(origin, direction))
+            .sum::<f64>()
+            / self.primitives.len() as f64
+    }
+
+    fn random(&self, origin: DVec3) -> DVec3 {
+        let index = rand::thread_rng().gen_range(0..self.primitives.len());
+
+        self.primitives[index].random(origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::{material::lambertian::Lambertian, sphere::Sphere};
+
+    #[test]
+    fn linear_matches_tree() {
+        let material = Arc::new(Lambertian::with_solid(DVec3::ONE));
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let spheres = (0..200)
+            .map(|_| {
+                let position = DVec3::new(
+                    rng.gen_range(-10.0..10.0),
+                    rng.gen_range(-10.0..10.0),
+                    rng.gen_range(-10.0..10.0),
+                );
+                Box::new(Sphere::stationary(position, 0.5, material.clone())) as Box<dyn Hittable>
+            })
+            .collect::<Vec<_>>();
+
+        let list = HittableList::new(spheres);
+        let rays = (0..500)
+            .map(|_| {
+                let direction = DVec3::new(
+                    rng.gen_range(-1.0..1.0),
+                    rng.gen_range(-1.0..1.0),
+                    rng.gen_range(-1.0..1.0),
+                );
+                Ray::new(DVec3::ZERO, direction)
+            })
+            .collect::<Vec<_>>();
+
+        let expected = rays
+            .iter()
+            .map(|ray| list.hit(*ray, 0.001..f64::INFINITY).map(|hit| hit.t))
+            .collect::<Vec<_>>();
+
+        let linear = LinearBoundingVolumeHierarchy::from(list);
+        let actual = rays
+            .iter()
+            .map(|ray| linear.hit(*ray, 0.001..f64::INFINITY).map(|hit| hit.t))
+            .collect::<Vec<_>>();
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn deeper_than_stack() {
+        let material = Arc::new(Lambertian::with_solid(DVec3::ONE));
+
+        // Exponentially spaced spheres make the surface area heuristic peel off one at a time.
+        let spheres = (0..100)
+            .map(|i| {
+                let position = DVec3::new(13.0f64.powi(i), 0.0, 0.0);
+                Box::new(Sphere::stationary(position, 1.0, material.clone())) as Box<dyn Hittable>
+            })
+            .collect::<Vec<_>>();
+        let linear = LinearBoundingVolumeHierarchy::new(spheres);
+
+        fn depth(nodes: &[LinearNode], index: usize) -> usize {
+            match nodes[index].kind {
+                NodeKind::Leaf { .. } => 1,
+                NodeKind::Interior { second_child, .. } => {
+                    1 + depth(nodes, index + 1).max(depth(nodes, second_child))
+                }
+            }
+        }
+        assert!(depth(&linear.nodes, 0) > STACK_SIZE);
+
+        // Along the row every level leaves a far child behind.
+        let ray = Ray::new(DVec3::new(-10.0, 0.0, 0.0), DVec3::X);
+        let hit = linear.hit(ray, 0.001..f64::INFINITY).unwrap();
+        assert!((hit.t - 10.0).abs() < 1e-9);
+
+        let ray = Ray::new(DVec3::new(13.0f64.powi(3), 0.0, 5.0), DVec3::NEG_Z);
+        let hit = linear.hit(ray, 0.001..f64::INFINITY).unwrap();
+        assert!((hit.t - 4.0).abs() < 1e-6);
+    }
+}
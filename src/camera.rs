@@ -1,8 +1,4 @@
-use std::{
-    fs::{self, File},
-    io::{Error, Write},
-    path::PathBuf,
-};
+use std::path::Path;
 
 use glam::DVec3;
 use indicatif::ParallelProgressIterator;
@@ -10,9 +6,38 @@ use itertools::Itertools;
 use rand::Rng;
 use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 
-use crate::{hittable::Hittable, material::util::random_in_unit_disk, ray::Ray};
+use crate::{
+    background::Background,
+    hittable::{HitRecord, Hittable},
+    material::util::random_in_unit_disk,
+    ray::Ray,
+    spectrum::{self, SampledWavelengths},
+};
 
 pub mod builder;
+pub mod output;
+pub mod tone_mapping;
+
+use output::{SaveError, DEFAULT_JPEG_QUALITY};
+use tone_mapping::ToneMapping;
+
+/// Which parts of the light transport depend on the wavelength.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SpectralMode {
+    /// Plain RGB rendering.
+    #[default]
+    Off,
+    /// Every sample carries a wavelength for dispersive materials while colors stay RGB. Each
+    /// sample is tinted by the response to its wavelength, so every path picks up color noise
+    /// which averages out to its RGB color unless the path depends on the wavelength.
+    Dispersion,
+    /// Every sample carries three wavelengths spread over the spectrum, see
+    /// [`SampledWavelengths`]. Colors from textures, emission and the background are upsampled
+    /// where they are looked up, every bounce is evaluated per wavelength and the film turns
+    /// the result into a color. Lights and filters then mix like real spectra instead of per
+    /// RGB channel, and dispersive materials only follow the first wavelength.
+    Full,
+}
 
 pub struct Camera {
     look_from: DVec3,
@@ -24,10 +49,15 @@ pub struct Camera {
     pixel00_loc: DVec3,
     samples_per_pixel: u32,
     max_depth: u32,
+    background: Background,
+    lights: Option<Box<dyn Hittable>>,
+    spectral: SpectralMode,
+    tone_mapping: ToneMapping,
     image: Image,
 }
 
 impl Camera {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         look_from: DVec3,
         look_at: DVec3,
@@ -37,6 +67,10 @@ impl Camera {
         focus_dist: f64,
         samples_per_pixel: u32,
         max_depth: u32,
+        background: Background,
+        lights: Option<Box<dyn Hittable>>,
+        spectral: SpectralMode,
+        tone_mapping: ToneMapping,
         image: Image,
     ) -> Self {
         let w = (look_from - look_at).normalize();
@@ -64,7 +98,7 @@ impl Camera {
         let defocus_disk_v = v * defocus_radius;
 
         Self {
-            look_from: look_from,
+            look_from,
             pixel_delta_u,
             pixel_delta_v,
             defocus_angle,
@@ -73,6 +107,10 @@ impl Camera {
             pixel00_loc,
             samples_per_pixel,
             max_depth,
+            background,
+            lights,
+            spectral,
+            tone_mapping,
             image,
         }
     }
@@ -83,7 +121,7 @@ impl Camera {
             .collect::<Vec<(u32, u32)>>()
             .into_par_iter()
             .map(|(y, x)| self.render_pixel((x, y), world))
-            .collect::<Vec<Pixel>>();
+            .collect::<Vec<DVec3>>();
 
         self.image.data = Some(pixels);
     }
@@ -95,44 +133,106 @@ impl Camera {
             .into_par_iter()
             .progress_count(self.image.width as u64 * self.image.height as u64)
             .map(|(y, x)| self.render_pixel((x, y), world))
-            .collect::<Vec<Pixel>>();
+            .collect::<Vec<DVec3>>();
 
         self.image.data = Some(pixels);
     }
 
-    fn render_pixel(&self, (x, y): (u32, u32), world: &dyn Hittable) -> Pixel {
+    fn render_pixel(&self, (x, y): (u32, u32), world: &dyn Hittable) -> DVec3 {
         let mut color = DVec3::ZERO;
 
-        for _ in 0..self.samples_per_pixel {
-            let ray = self.get_ray(x, y);
-            color += self.ray_color(ray, self.max_depth, world);
-        }
+        for sample in 0..self.samples_per_pixel {
+            let mut ray = self.get_ray(x, y);
 
-        let normalized = color * (1.0 / self.samples_per_pixel as f64);
-        let gamma = Self::linear_to_gamma(normalized);
+            if self.spectral != SpectralMode::Off {
+                // Stratified over the samples of the pixel so every pixel sees the whole spectrum.
+                let u = (sample as f64 + rand::thread_rng().gen_range(0.0..1.0))
+                    / self.samples_per_pixel as f64;
+
+                ray.wavelengths = match self.spectral {
+                    SpectralMode::Full => SampledWavelengths::stratified(u),
+                    _ => SampledWavelengths::Hero(spectrum::sample_wavelength(u)),
+                };
+            }
+
+            color += ray
+                .wavelengths
+                .to_rgb(self.ray_color(ray, self.max_depth, world, None));
+        }
 
-        (gamma.clamp(DVec3::splat(0.0), DVec3::splat(0.999)) * self.image.max_color_value as f64)
-            .into()
+        color * (1.0 / self.samples_per_pixel as f64)
     }
 
-    fn ray_color(&self, ray: Ray, depth: u32, world: &dyn Hittable) -> DVec3 {
+    /// `scattering_pdf` is the density with which the previous bounce chose `ray`, it is `None`
+    /// for camera rays and specular bounces which can't be combined with light sampling.
+    fn ray_color(
+        &self,
+        ray: Ray,
+        depth: u32,
+        world: &dyn Hittable,
+        scattering_pdf: Option<f64>,
+    ) -> DVec3 {
         if depth == 0 {
             return DVec3::ZERO;
         }
 
-        if let Some(hit) = world.hit(ray, 0.001..f64::INFINITY) {
-            if let Some(scattered) = hit.material.clone().scatter(ray, hit) {
-                return scattered.attenuation
-                    * self.ray_color(scattered.direction, depth - 1, world);
-            } else {
-                return DVec3::ZERO;
+        let Some(hit) = world.hit(ray, 0.001..f64::INFINITY) else {
+            return self.background.color(ray.direction, ray.wavelengths);
+        };
+
+        let emitted = hit.material.emitted(hit.uv, hit.point, ray.wavelengths);
+        let emitted_weight = match (&self.lights, scattering_pdf) {
+            (Some(lights), Some(scattering_pdf)) => {
+                power_heuristic(scattering_pdf, lights.pdf_value(ray.origin, ray.direction))
             }
+            _ => 1.0,
+        };
+
+        let Some(scattered) = hit.material.scatter(ray, hit.clone()) else {
+            return emitted * emitted_weight;
+        };
+
+        // Even after a specular bounce, since mixed materials can have other lobes to sample
+        // lights for. Purely specular materials evaluate to zero and skip the shadow ray.
+        let direct_light = self.sample_lights(ray, &hit, world);
+
+        emitted * emitted_weight
+            + direct_light
+            + scattered.attenuation
+                * self.ray_color(scattered.direction, depth - 1, world, scattered.pdf)
+    }
+
+    /// Next event estimation: one light sample weighted against the material's own sampling.
+    fn sample_lights(&self, ray: Ray, hit: &HitRecord, world: &dyn Hittable) -> DVec3 {
+        let Some(lights) = &self.lights else {
+            return DVec3::ZERO;
+        };
+
+        let direction = lights.random(hit.point);
+        let light_pdf = lights.pdf_value(hit.point, direction);
+
+        if light_pdf <= 0.0 {
+            return DVec3::ZERO;
+        }
+
+        let bsdf = hit.material.evaluate(ray, hit, direction);
+
+        if bsdf == DVec3::ZERO {
+            return DVec3::ZERO;
         }
 
-        let unit_direction = ray.direction.normalize();
-        let a = 0.5 * (unit_direction.y + 1.0);
+        let shadow_ray = ray.spawn(hit.point, direction);
+
+        let Some(light_hit) = world.hit(shadow_ray, 0.001..f64::INFINITY) else {
+            return DVec3::ZERO;
+        };
+
+        let emitted = light_hit
+            .material
+            .emitted(light_hit.uv, light_hit.point, ray.wavelengths);
+        let scattering_pdf = hit.material.scattering_pdf(ray, hit, direction);
 
-        (1.0 - a) * DVec3::new(1.0, 1.0, 1.0) + a * DVec3::new(0.5, 0.7, 1.0)
+        bsdf * emitted * power_heuristic(light_pdf, scattering_pdf) / light_pdf
     }
 
     fn get_ray(&self, x: u32, y: u32) -> Ray {
@@ -159,40 +259,41 @@ impl Camera {
         (px * self.pixel_delta_u) + (py * self.pixel_delta_v)
     }
 
-    fn linear_to_gamma(color: DVec3) -> DVec3 {
-        DVec3::new(color.x.sqrt(), color.y.sqrt(), color.z.sqrt())
-    }
-
     fn defocus_disk_sample(&self) -> DVec3 {
         let p = random_in_unit_disk();
 
         self.look_from + (p.x * self.defocus_disk_u) + (p.y * self.defocus_disk_v)
     }
 
-    pub fn save_image(&self, name: &str) -> Result<(), Error> {
-        let pixels = if let Some(pixels) = &self.image.data {
-            pixels
-                .iter()
-                .map(|pixel| format!("{} {} {}", pixel.r, pixel.g, pixel.b))
-                .collect::<Vec<String>>()
-                .join("\n")
-        } else {
-            todo!();
-        };
+    pub fn image(&self) -> &Image {
+        &self.image
+    }
 
-        let path = PathBuf::from(format!("{}/{}.ppm", crate::IMAGES_FOLDER, name));
+    /// Saves the rendered image, the format is chosen by the extension of `path`: `png`, `jpg`
+    /// or `jpeg`, `ppm` (binary), `bmp` and `tga` are tone mapped, `exr`, `hdr` and `pfm` keep
+    /// the linear radiance.
+    pub fn save_image(&self, path: impl AsRef<Path>) -> Result<(), SaveError> {
+        self.save_image_with_quality(path, DEFAULT_JPEG_QUALITY)
+    }
 
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)?;
-        }
+    /// Like [`Camera::save_image`] but with a JPEG quality between 1 and 100.
+    pub fn save_image_with_quality(
+        &self,
+        path: impl AsRef<Path>,
+        jpeg_quality: u8,
+    ) -> Result<(), SaveError> {
+        output::save(&self.image, &self.tone_mapping, path.as_ref(), jpeg_quality)
+    }
+}
 
-        let output = format!(
-            "P3\n{} {}\n{}\n{}\n",
-            self.image.width, self.image.height, self.image.max_color_value, pixels
-        );
-        let mut file = File::create(path)?;
+fn power_heuristic(pdf: f64, other_pdf: f64) -> f64 {
+    let squared = pdf * pdf;
+    let other_squared = other_pdf * other_pdf;
 
-        write!(file, "{output}")
+    if squared + other_squared == 0.0 {
+        0.0
+    } else {
+        squared / (squared + other_squared)
     }
 }
 
@@ -200,7 +301,7 @@ pub struct Image {
     width: u32,
     height: u32,
     max_color_value: u32,
-    data: Option<Vec<Pixel>>,
+    data: Option<Vec<DVec3>>,
 }
 
 impl Image {
@@ -222,23 +323,134 @@ impl Image {
         }
     }
 
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// The rendered linear radiance in row major order, starting at the top left.
+    pub fn radiance(&self) -> Option<&[DVec3]> {
+        self.data.as_deref()
+    }
+
     fn aspect_ratio(&self) -> f64 {
         self.width as f64 / self.height as f64
     }
 }
 
-struct Pixel {
-    r: u32,
-    g: u32,
-    b: u32,
-}
-
-impl From<DVec3> for Pixel {
-    fn from(value: DVec3) -> Self {
-        Pixel {
-            r: value.x as u32,
-            g: value.y as u32,
-            b: value.z as u32,
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        camera::builder::CameraBuilder,
+        hittable::HittableList,
+        material::{
+            diffuse_light::DiffuseLight, lambertian::Lambertian, metal::Metal, mix::MixMaterial,
+            Material,
+        },
+        quad::Quad,
+        sphere::Sphere,
+    };
+
+    /// Renders a single pixel of a floor lit by a sphere of radiance 8 and radius 1 hovering
+    /// 4 units above it, straight above the camera.
+    fn render_floor(
+        floor: Arc<dyn Material>,
+        with_lights: bool,
+        samples_per_pixel: u32,
+        spectral: SpectralMode,
+    ) -> DVec3 {
+        let light = Arc::new(DiffuseLight::with_solid(DVec3::splat(8.0)));
+        let sphere = || Sphere::stationary(DVec3::new(0.0, 4.0, 0.0), 1.0, light.clone());
+
+        let world = HittableList::new(vec![
+            Box::new(Quad::new(
+                DVec3::new(-50.0, 0.0, -50.0),
+                DVec3::Z * 100.0,
+                DVec3::X * 100.0,
+                floor,
+            )),
+            Box::new(sphere()),
+        ]);
+
+        let mut builder = CameraBuilder::default()
+            .look_from(DVec3::Y)
+            .look_at(DVec3::ZERO)
+            .up(DVec3::Z)
+            .fov(1.0)
+            .samples_per_pixel(samples_per_pixel)
+            .max_depth(4)
+            .background(Background::Solid(DVec3::ZERO))
+            .spectral(spectral)
+            .image(Image::from_width_height(1, 1, 255));
+        if with_lights {
+            builder = builder.lights(sphere());
         }
+
+        let mut camera = builder.build();
+        camera.render_image(&world);
+
+        camera.image().radiance().unwrap()[0]
+    }
+
+    #[test]
+    fn light_sampling() {
+        // The sphere covers a cone with sin² of its half angle (1/4)², a Lambertian floor
+        // of albedo 0.5 below it reflects 0.5 * 8 / 16.
+        let floor = Arc::new(Lambertian::with_solid(DVec3::splat(0.5)));
+        let expected = DVec3::splat(0.25);
+
+        let without_lights = render_floor(floor.clone(), false, 200000, SpectralMode::Off);
+        assert!(
+            without_lights.abs_diff_eq(expected, 0.0125),
+            "{without_lights}"
+        );
+
+        let with_lights = render_floor(floor, true, 2000, SpectralMode::Off);
+        assert!(with_lights.abs_diff_eq(expected, 0.0125), "{with_lights}");
+    }
+
+    #[test]
+    fn light_sampling_after_specular_pick() {
+        // Half the hits reflect 0.25 off the Lambertian, the other half see the light in the
+        // mirror.
+        let floor = Arc::new(MixMaterial::with_weight(
+            Arc::new(Lambertian::with_solid(DVec3::splat(0.5))),
+            Arc::new(Metal::new(DVec3::ONE, 0.0)),
+            0.5,
+        ));
+        let expected = DVec3::splat(0.5 * 0.25 + 0.5 * 8.0);
+
+        let without_lights = render_floor(floor.clone(), false, 200000, SpectralMode::Off);
+        assert!(
+            without_lights.abs_diff_eq(expected, 0.05),
+            "{without_lights}"
+        );
+
+        let with_lights = render_floor(floor, true, 200000, SpectralMode::Off);
+        assert!(with_lights.abs_diff_eq(expected, 0.05), "{with_lights}");
+    }
+
+    #[test]
+    fn spectral_rendering() {
+        // A white light is the D65 illuminant and the film maps it back to white, so gray
+        // surfaces render like in RGB.
+        let gray = Arc::new(Lambertian::with_solid(DVec3::splat(0.5)));
+        let spectral = render_floor(gray, true, 4000, SpectralMode::Full);
+        assert!(
+            spectral.abs_diff_eq(DVec3::splat(0.25), 0.0125),
+            "{spectral}"
+        );
+
+        // Colors are upsampled at the lookup and only come back close to their RGB value.
+        let albedo = DVec3::new(0.8, 0.3, 0.1);
+        let colored = Arc::new(Lambertian::with_solid(albedo));
+        let spectral = render_floor(colored, true, 4000, SpectralMode::Full);
+        assert!(spectral.abs_diff_eq(0.5 * albedo, 0.05), "{spectral}");
     }
 }
@@ -1,6 +1,11 @@
 use glam::DVec3;
 
-use super::{Camera, Image};
+use crate::{background::Background, color::TransferFunction, hittable::Hittable};
+
+use super::{
+    tone_mapping::{ToneMapOperator, ToneMapping},
+    Camera, Image, SpectralMode,
+};
 
 pub struct CameraBuilder {
     look_from: DVec3,
@@ -11,6 +16,10 @@ pub struct CameraBuilder {
     focus_dist: f64,
     samples_per_pixel: u32,
     max_depth: u32,
+    background: Background,
+    lights: Option<Box<dyn Hittable>>,
+    spectral: SpectralMode,
+    tone_mapping: ToneMapping,
     image: Image,
 }
 
@@ -25,6 +34,10 @@ impl Default for CameraBuilder {
             focus_dist: 10.0,
             samples_per_pixel: 100,
             max_depth: 50,
+            background: Background::default(),
+            lights: None,
+            spectral: SpectralMode::Off,
+            tone_mapping: ToneMapping::default(),
             image: Image::from_width_aspect_ratio(400, 16.0 / 9.0, 255),
         }
     }
@@ -71,6 +84,40 @@ impl CameraBuilder {
         self
     }
 
+    pub fn background(mut self, background: Background) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Emitters that are sampled explicitly at every diffuse bounce. They are only used to pick
+    /// directions, the emitted light itself still comes from the objects in the world.
+    pub fn lights(mut self, lights: impl Hittable + 'static) -> Self {
+        self.lights = Some(Box::new(lights));
+        self
+    }
+
+    /// Gives every sample its own wavelengths, see [`SpectralMode`].
+    pub fn spectral(mut self, spectral: SpectralMode) -> Self {
+        self.spectral = spectral;
+        self
+    }
+
+    pub fn tone_map(mut self, operator: ToneMapOperator) -> Self {
+        self.tone_mapping.operator = operator;
+        self
+    }
+
+    /// Exposure compensation in stops.
+    pub fn exposure(mut self, exposure: f64) -> Self {
+        self.tone_mapping.exposure = exposure;
+        self
+    }
+
+    pub fn transfer_function(mut self, transfer_function: TransferFunction) -> Self {
+        self.tone_mapping.transfer_function = transfer_function;
+        self
+    }
+
     pub fn image(mut self, image: Image) -> Self {
         self.image = image;
         self
@@ -86,6 +133,10 @@ impl CameraBuilder {
             self.focus_dist,
             self.samples_per_pixel,
             self.max_depth,
+            self.background,
+            self.lights,
+            self.spectral,
+            self.tone_mapping,
             self.image,
         )
     }
@@ -0,0 +1,305 @@
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use glam::DVec3;
+use image::{
+    codecs::{hdr::HdrEncoder, jpeg::JpegEncoder},
+    ImageError, ImageFormat, Rgb, Rgb32FImage, RgbImage,
+};
+
+use super::{tone_mapping::ToneMapping, Image};
+
+pub const DEFAULT_JPEG_QUALITY: u8 = 90;
+
+#[derive(Debug)]
+pub enum SaveError {
+    NotRendered,
+    /// PPM stores at most two bytes per channel, so the maximum is 65535.
+    InvalidMaxColorValue(u32),
+    UnsupportedFormat(String),
+    Io(io::Error),
+    Image(ImageError),
+}
+
+impl Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::NotRendered => write!(f, "the image has not been rendered yet"),
+            SaveError::InvalidMaxColorValue(value) => {
+                write!(f, "max color value {value} is not between 1 and 65535")
+            }
+            SaveError::UnsupportedFormat(extension) => {
+                write!(f, "unsupported image format '{extension}'")
+            }
+            SaveError::Io(error) => write!(f, "{error}"),
+            SaveError::Image(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl Error for SaveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SaveError::Io(error) => Some(error),
+            SaveError::Image(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SaveError {
+    fn from(value: io::Error) -> Self {
+        SaveError::Io(value)
+    }
+}
+
+impl From<ImageError> for SaveError {
+    fn from(value: ImageError) -> Self {
+        SaveError::Image(value)
+    }
+}
+
+enum OutputFormat {
+    Ppm,
+    Jpeg,
+    Other(ImageFormat),
+    OpenExr,
+    RadianceHdr,
+    Pfm,
+}
+
+impl OutputFormat {
+    fn from_path(path: &Path) -> Result<Self, SaveError> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        match extension.as_str() {
+            "ppm" => Ok(OutputFormat::Ppm),
+            "jpg" | "jpeg" => Ok(OutputFormat::Jpeg),
+            "png" => Ok(OutputFormat::Other(ImageFormat::Png)),
+            "bmp" => Ok(OutputFormat::Other(ImageFormat::Bmp)),
+            "tga" => Ok(OutputFormat::Other(ImageFormat::Tga)),
+            "exr" => Ok(OutputFormat::OpenExr),
+            "hdr" => Ok(OutputFormat::RadianceHdr),
+            "pfm" => Ok(OutputFormat::Pfm),
+            _ => Err(SaveError::UnsupportedFormat(extension)),
+        }
+    }
+}
+
+pub(super) fn save(
+    image: &Image,
+    tone_mapping: &ToneMapping,
+    path: &Path,
+    jpeg_quality: u8,
+) -> Result<(), SaveError> {
+    let radiance = image.data.as_ref().ok_or(SaveError::NotRendered)?;
+
+    if !(1..=u16::MAX as u32).contains(&image.max_color_value) {
+        return Err(SaveError::InvalidMaxColorValue(image.max_color_value));
+    }
+
+    let format = OutputFormat::from_path(path)?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    match format {
+        OutputFormat::Ppm => write_ppm(image, &quantize(image, tone_mapping, radiance), path),
+        OutputFormat::Jpeg => {
+            let writer = BufWriter::new(File::create(path)?);
+            let mut encoder = JpegEncoder::new_with_quality(writer, jpeg_quality);
+
+            Ok(encoder.encode_image(&to_rgb8(image, &quantize(image, tone_mapping, radiance)))?)
+        }
+        OutputFormat::Other(format) => Ok(to_rgb8(image, &quantize(image, tone_mapping, radiance))
+            .save_with_format(path, format)?),
+        OutputFormat::OpenExr => {
+            Ok(to_rgb32f(image, radiance).save_with_format(path, ImageFormat::OpenExr)?)
+        }
+        OutputFormat::RadianceHdr => {
+            let writer = BufWriter::new(File::create(path)?);
+            let pixels = radiance
+                .iter()
+                .map(|color| Rgb(color.as_vec3().to_array()))
+                .collect::<Vec<Rgb<f32>>>();
+
+            Ok(HdrEncoder::new(writer).encode(
+                &pixels,
+                image.width as usize,
+                image.height as usize,
+            )?)
+        }
+        OutputFormat::Pfm => write_pfm(image, radiance, path),
+    }
+}
+
+struct Pixel {
+    r: u32,
+    g: u32,
+    b: u32,
+}
+
+fn quantize(image: &Image, tone_mapping: &ToneMapping, radiance: &[DVec3]) -> Vec<Pixel> {
+    radiance
+        .iter()
+        .map(|color| {
+            let encoded = tone_mapping.apply(*color);
+            let scaled = encoded.clamp(DVec3::splat(0.0), DVec3::splat(0.999))
+                * image.max_color_value as f64;
+
+            Pixel {
+                r: scaled.x as u32,
+                g: scaled.y as u32,
+                b: scaled.z as u32,
+            }
+        })
+        .collect()
+}
+
+/// Binary P6 PPM, using two bytes per channel if `max_color_value` doesn't fit into one.
+fn write_ppm(image: &Image, pixels: &[Pixel], path: &Path) -> Result<(), SaveError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+
+    write!(
+        writer,
+        "P6\n{} {}\n{}\n",
+        image.width, image.height, image.max_color_value
+    )?;
+
+    for pixel in pixels {
+        for channel in [pixel.r, pixel.g, pixel.b] {
+            if image.max_color_value < 256 {
+                writer.write_all(&[channel as u8])?;
+            } else {
+                writer.write_all(&(channel as u16).to_be_bytes())?;
+            }
+        }
+    }
+
+    Ok(writer.flush()?)
+}
+
+/// Little endian PFM, which stores its rows from bottom to top.
+fn write_pfm(image: &Image, radiance: &[DVec3], path: &Path) -> Result<(), SaveError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+
+    write!(writer, "PF\n{} {}\n-1.0\n", image.width, image.height)?;
+
+    for row in radiance.chunks(image.width as usize).rev() {
+        for color in row {
+            for channel in color.as_vec3().to_array() {
+                writer.write_all(&channel.to_le_bytes())?;
+            }
+        }
+    }
+
+    Ok(writer.flush()?)
+}
+
+fn to_rgb32f(image: &Image, radiance: &[DVec3]) -> Rgb32FImage {
+    let data = radiance
+        .iter()
+        .flat_map(|color| color.as_vec3().to_array())
+        .collect();
+
+    Rgb32FImage::from_raw(image.width, image.height, data)
+        .expect("radiance buffer matches the image size")
+}
+
+fn to_rgb8(image: &Image, pixels: &[Pixel]) -> RgbImage {
+    let scale = 255.0 / image.max_color_value as f64;
+    let data = pixels
+        .iter()
+        .flat_map(|pixel| [pixel.r, pixel.g, pixel.b])
+        .map(|channel| (channel as f64 * scale).round().min(255.0) as u8)
+        .collect();
+
+    RgbImage::from_raw(image.width, image.height, data)
+        .expect("pixel buffer matches the image size")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    #[test]
+    fn save_formats() {
+        let mut image = Image::from_width_height(2, 1, 255);
+        let directory = env::temp_dir().join(format!("rust_tracer_{}_save_formats", process::id()));
+
+        assert!(matches!(
+            save(
+                &image,
+                &ToneMapping::default(),
+                &directory.join("image.png"),
+                DEFAULT_JPEG_QUALITY
+            ),
+            Err(SaveError::NotRendered)
+        ));
+
+        image.data = Some(vec![DVec3::new(4.0, 0.0, 0.0), DVec3::new(0.0, 0.0, 0.25)]);
+
+        assert!(matches!(
+            save(
+                &image,
+                &ToneMapping::default(),
+                &directory.join("image.gif"),
+                DEFAULT_JPEG_QUALITY
+            ),
+            Err(SaveError::UnsupportedFormat(_))
+        ));
+
+        for extension in ["png", "jpg", "bmp", "tga", "exr", "hdr"] {
+            let path = directory.join(format!("image.{extension}"));
+            save(&image, &ToneMapping::default(), &path, DEFAULT_JPEG_QUALITY).unwrap();
+
+            let loaded = image::open(&path).unwrap().into_rgb8();
+            assert_eq!(loaded.dimensions(), (2, 1));
+        }
+
+        let path = directory.join("image.ppm");
+        save(&image, &ToneMapping::default(), &path, DEFAULT_JPEG_QUALITY).unwrap();
+
+        assert_eq!(
+            fs::read(&path).unwrap(),
+            b"P6\n2 1\n255\n\xfe\x00\x00\x00\x00\x88".to_vec()
+        );
+
+        let loaded = image::open(directory.join("image.exr"))
+            .unwrap()
+            .into_rgb32f();
+        assert_eq!(loaded.get_pixel(0, 0).0, [4.0, 0.0, 0.0]);
+
+        let path = directory.join("image.pfm");
+        save(&image, &ToneMapping::default(), &path, DEFAULT_JPEG_QUALITY).unwrap();
+
+        let pfm = fs::read(&path).unwrap();
+        assert!(pfm.starts_with(b"PF\n2 1\n-1.0\n"));
+        assert_eq!(pfm[pfm.len() - 24..pfm.len() - 20], 4.0f32.to_le_bytes());
+
+        image.max_color_value = 65536;
+        assert!(matches!(
+            save(
+                &image,
+                &ToneMapping::default(),
+                &directory.join("image.ppm"),
+                DEFAULT_JPEG_QUALITY
+            ),
+            Err(SaveError::InvalidMaxColorValue(65536))
+        ));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
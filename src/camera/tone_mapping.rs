@@ -0,0 +1,173 @@
+use glam::{DMat3, DVec3};
+
+use crate::color::TransferFunction;
+
+/// Maps scene radiance onto the displayable range before it is written to 8 bit formats.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum ToneMapOperator {
+    /// Cuts off everything brighter than 1.
+    #[default]
+    Clamp,
+    Reinhard,
+    /// Reinhard which maps the luminance `white_point` to 1 instead of infinity.
+    ExtendedReinhard {
+        white_point: f64,
+    },
+    /// Krzysztof Narkowicz's fit of the ACES filmic curve.
+    AcesFilmic,
+    /// John Hable's Uncharted 2 curve.
+    Hable,
+    /// Troy Sobotka's AgX with the default contrast look.
+    Agx,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ToneMapping {
+    pub operator: ToneMapOperator,
+    /// Exposure compensation in stops, every stop doubles the brightness.
+    pub exposure: f64,
+    pub transfer_function: TransferFunction,
+}
+
+impl ToneMapping {
+    /// Turns linear radiance into encoded values between 0 and 1.
+    pub fn apply(&self, radiance: DVec3) -> DVec3 {
+        let exposed = radiance.max(DVec3::ZERO) * self.exposure.exp2();
+        let mapped = self.operator.apply(exposed).clamp(DVec3::ZERO, DVec3::ONE);
+
+        self.transfer_function.encode(mapped)
+    }
+}
+
+impl ToneMapOperator {
+    pub fn apply(&self, color: DVec3) -> DVec3 {
+        match self {
+            ToneMapOperator::Clamp => color,
+            ToneMapOperator::Reinhard => {
+                scale_luminance(color, |luminance| luminance / (1.0 + luminance))
+            }
+            ToneMapOperator::ExtendedReinhard { white_point } => {
+                let white_squared = white_point * white_point;
+
+                scale_luminance(color, |luminance| {
+                    luminance * (1.0 + luminance / white_squared) / (1.0 + luminance)
+                })
+            }
+            ToneMapOperator::AcesFilmic => {
+                (color * (2.51 * color + 0.03)) / (color * (2.43 * color + 0.59) + 0.14)
+            }
+            ToneMapOperator::Hable => {
+                const EXPOSURE_BIAS: f64 = 2.0;
+                const WHITE_POINT: f64 = 11.2;
+
+                hable_partial(color * EXPOSURE_BIAS) / hable_partial(DVec3::splat(WHITE_POINT))
+            }
+            ToneMapOperator::Agx => agx(color),
+        }
+    }
+}
+
+fn luminance(color: DVec3) -> f64 {
+    color.dot(DVec3::new(0.2126, 0.7152, 0.0722))
+}
+
+fn scale_luminance(color: DVec3, curve: impl Fn(f64) -> f64) -> DVec3 {
+    let luminance = luminance(color);
+
+    if luminance <= 0.0 {
+        DVec3::ZERO
+    } else {
+        color * (curve(luminance) / luminance)
+    }
+}
+
+fn hable_partial(x: DVec3) -> DVec3 {
+    const A: f64 = 0.15;
+    const B: f64 = 0.50;
+    const C: f64 = 0.10;
+    const D: f64 = 0.20;
+    const E: f64 = 0.02;
+    const F: f64 = 0.30;
+
+    (x * (A * x + C * B) + D * E) / (x * (A * x + B) + D * F) - E / F
+}
+
+/// Minimal AgX following Benjamin Wrensch's fit, returns linear display values.
+fn agx(color: DVec3) -> DVec3 {
+    const MIN_EV: f64 = -12.47393;
+    const MAX_EV: f64 = 4.026069;
+
+    let inset = DMat3::from_cols_array(&[
+        0.842479062253094,
+        0.0423282422610123,
+        0.0423756549057051,
+        0.0784335999999992,
+        0.878468636469772,
+        0.0784336,
+        0.0792237451477643,
+        0.0791661274605434,
+        0.879142973793104,
+    ]);
+    let outset = DMat3::from_cols_array(&[
+        1.19687900512017,
+        -0.0528968517574562,
+        -0.0529716355144438,
+        -0.0980208811401368,
+        1.15190312990417,
+        -0.0980434501171241,
+        -0.0990297440797205,
+        -0.0989611768448433,
+        1.15107367264116,
+    ]);
+
+    let encoded = DVec3::from_array((inset * color).to_array().map(|channel| {
+        (channel.max(f64::MIN_POSITIVE).log2().clamp(MIN_EV, MAX_EV) - MIN_EV) / (MAX_EV - MIN_EV)
+    }));
+
+    let x2 = encoded * encoded;
+    let x4 = x2 * x2;
+    let contrast = 15.5 * x4 * x2 - 40.14 * x4 * encoded + 31.96 * x4 - 6.868 * x2 * encoded
+        + 0.4298 * x2
+        + 0.1191 * encoded
+        - 0.00232;
+
+    (outset * contrast).max(DVec3::ZERO).powf(2.2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tone_mapping() {
+        let operators = [
+            ToneMapOperator::Reinhard,
+            ToneMapOperator::ExtendedReinhard { white_point: 4.0 },
+            ToneMapOperator::AcesFilmic,
+            ToneMapOperator::Hable,
+            ToneMapOperator::Agx,
+        ];
+
+        for operator in operators {
+            let tone_mapping = ToneMapping {
+                operator,
+                ..Default::default()
+            };
+            let dark = tone_mapping.apply(DVec3::splat(0.1));
+            let bright = tone_mapping.apply(DVec3::splat(100.0));
+
+            assert!(dark.x > 0.0 && dark.x < bright.x, "{operator:?}");
+            assert!(bright.x <= 1.0, "{operator:?}");
+        }
+
+        let white = ToneMapOperator::ExtendedReinhard { white_point: 4.0 }.apply(DVec3::splat(4.0));
+        assert!(white.abs_diff_eq(DVec3::ONE, 1e-12));
+
+        let tone_mapping = ToneMapping {
+            exposure: -1.0,
+            transfer_function: TransferFunction::Linear,
+            ..Default::default()
+        };
+        assert_eq!(tone_mapping.apply(DVec3::splat(1.0)), DVec3::splat(0.5));
+    }
+}
@@ -0,0 +1,64 @@
+use glam::DVec3;
+
+/// Encodes linear values for storage in 8 bit images and decodes them back.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum TransferFunction {
+    Linear,
+    Gamma(f64),
+    /// The piecewise sRGB curve.
+    #[default]
+    Srgb,
+}
+
+impl TransferFunction {
+    pub fn encode(&self, color: DVec3) -> DVec3 {
+        match self {
+            TransferFunction::Linear => color,
+            TransferFunction::Gamma(gamma) => color.powf(gamma.recip()),
+            TransferFunction::Srgb => DVec3::from_array(color.to_array().map(|channel| {
+                if channel <= 0.0031308 {
+                    12.92 * channel
+                } else {
+                    1.055 * channel.powf(2.4_f64.recip()) - 0.055
+                }
+            })),
+        }
+    }
+
+    pub fn decode(&self, color: DVec3) -> DVec3 {
+        match self {
+            TransferFunction::Linear => color,
+            TransferFunction::Gamma(gamma) => color.powf(*gamma),
+            TransferFunction::Srgb => DVec3::from_array(color.to_array().map(|channel| {
+                if channel <= 0.04045 {
+                    channel / 12.92
+                } else {
+                    ((channel + 0.055) / 1.055).powf(2.4)
+                }
+            })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transfer_function() {
+        let color = DVec3::new(0.001, 0.2, 0.9);
+
+        for transfer_function in [
+            TransferFunction::Linear,
+            TransferFunction::Gamma(2.2),
+            TransferFunction::Srgb,
+        ] {
+            let encoded = transfer_function.encode(color);
+
+            assert!(
+                transfer_function.decode(encoded).abs_diff_eq(color, 1e-12),
+                "{transfer_function:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::{ops::Range, sync::Arc};
+
+use glam::DVec3;
+
+use crate::{
+    hittable::{AxisAlignedBoundingBox, HitRecord, Hittable},
+    material::{isotropic::Isotropic, Material},
+    medium::{self, Coefficients},
+    ray::Ray,
+    texture::Texture,
+};
+
+/// Fog or smoke of uniform density filling a closed `boundary`.
+///
+/// Rays travelling through it scatter after a random distance with the phase function, so it is
+/// reported as a hit somewhere inside the volume rather than on its surface.
+#[derive(Debug)]
+pub struct ConstantMedium {
+    boundary: Arc<dyn Hittable>,
+    coefficients: Coefficients,
+    phase_function: Arc<dyn Material>,
+}
+
+impl ConstantMedium {
+    pub fn new(boundary: Arc<dyn Hittable>, density: f64, albedo: Texture) -> Self {
+        Self {
+            boundary,
+            coefficients: Coefficients {
+                sigma_a: DVec3::ZERO,
+                sigma_s: DVec3::splat(density),
+            },
+            phase_function: Arc::new(Isotropic::new(albedo)),
+        }
+    }
+
+    pub fn with_solid(boundary: Arc<dyn Hittable>, density: f64, albedo: DVec3) -> Self {
+        Self::new(boundary, density, Texture::Solid(albedo))
+    }
+
+    /// A medium with per channel [`Coefficients`] instead of a colored phase function.
+    pub fn from_coefficients(boundary: Arc<dyn Hittable>, sigma_a: DVec3, sigma_s: DVec3) -> Self {
+        Self {
+            boundary,
+            coefficients: Coefficients { sigma_a, sigma_s },
+            phase_function: Arc::new(Isotropic::with_solid(DVec3::ONE)),
+        }
+    }
+
+    /// See [phase functions](crate::medium#phase-functions).
+    pub fn with_phase_function(mut self, phase_function: Arc<dyn Material>) -> Self {
+        self.phase_function = phase_function;
+        self
+    }
+}
+
+impl Hittable for ConstantMedium {
+    fn hit(&self, ray: Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let span = medium::boundary_span(self.boundary.as_ref(), ray, t_range)?;
+
+        medium::track(
+            ray,
+            span,
+            self.coefficients,
+            1.0,
+            |_| 1.0,
+            &self.phase_function,
+        )
+    }
+
+    fn bounding_box(&self) -> &AxisAlignedBoundingBox {
+        self.boundary.bounding_box()
+    }
+
+    fn pdf_value(&self, origin: DVec3, direction: DVec3) -> f64 {
+        self.boundary.pdf_value(origin, direction)
+    }
+
+    fn random(&self, origin: DVec3) -> DVec3 {
+        self.boundary.random(origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{material::lambertian::Lambertian, sphere::Sphere};
+
+    #[test]
+    fn scatters_inside_boundary() {
+        let boundary = Arc::new(Sphere::stationary(
+            DVec3::ZERO,
+            1.0,
+            Arc::new(Lambertian::with_solid(DVec3::ONE)),
+        ));
+        let thin = ConstantMedium::with_solid(boundary.clone(), 1e-9, DVec3::ONE);
+        let dense = ConstantMedium::with_solid(boundary, 1e9, DVec3::ONE);
+
+        let outside = Ray::new(DVec3::new(0.0, 0.0, 5.0), DVec3::NEG_Z);
+        assert!(thin.hit(outside, 0.001..f64::INFINITY).is_none());
+
+        let hit = dense.hit(outside, 0.001..f64::INFINITY).unwrap();
+        assert!((hit.t - 4.0).abs() < 1e-6);
+
+        let inside = Ray::new(DVec3::ZERO, DVec3::X);
+        let hit = dense.hit(inside, 0.001..f64::INFINITY).unwrap();
+        assert!(hit.t < 1e-3 + 1e-6);
+
+        assert!(dense.hit(outside, 0.001..3.0).is_none());
+    }
+}
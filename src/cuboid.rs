@@ -0,0 +1,130 @@
+use std::{ops::Range, sync::Arc};
+
+use glam::DVec3;
+use rand::Rng;
+
+use crate::{
+    hittable::{AxisAlignedBoundingBox, HitRecord, Hittable},
+    material::Material,
+    quad::Quad,
+    ray::Ray,
+};
+
+/// An axis aligned box made of six quads, each face has its own `uv` from 0 to 1.
+#[derive(Debug)]
+pub struct Cuboid {
+    sides: [Quad; 6],
+    bounding_box: AxisAlignedBoundingBox,
+}
+
+impl Cuboid {
+    /// The corners have to differ on every axis, flat boxes have no normals on their edges.
+    pub fn new(corner0: DVec3, corner1: DVec3, material: Arc<dyn Material>) -> Self {
+        let min = corner0.min(corner1);
+        let max = corner0.max(corner1);
+        assert!(min.cmplt(max).all(), "degenerate cuboid {min} {max}");
+
+        let dx = DVec3::new(max.x - min.x, 0.0, 0.0);
+        let dy = DVec3::new(0.0, max.y - min.y, 0.0);
+        let dz = DVec3::new(0.0, 0.0, max.z - min.z);
+
+        let sides = [
+            Quad::new(DVec3::new(min.x, min.y, max.z), dx, dy, material.clone()),
+            Quad::new(DVec3::new(max.x, min.y, max.z), -dz, dy, material.clone()),
+            Quad::new(DVec3::new(max.x, min.y, min.z), -dx, dy, material.clone()),
+            Quad::new(DVec3::new(min.x, min.y, min.z), dz, dy, material.clone()),
+            Quad::new(DVec3::new(min.x, max.y, max.z), dx, -dz, material.clone()),
+            Quad::new(DVec3::new(min.x, min.y, min.z), dx, dz, material),
+        ];
+
+        Self {
+            sides,
+            bounding_box: AxisAlignedBoundingBox::from_corners(min, max).padded(),
+        }
+    }
+}
+
+impl Hittable for Cuboid {
+    fn hit(&self, ray: Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let mut closest_hit = None;
+        let mut closest_so_far = t_range.end;
+
+        for side in &self.sides {
+            if let Some(hit) = side.hit(ray, t_range.start..closest_so_far) {
+                closest_so_far = hit.t;
+                closest_hit = Some(hit);
+            }
+        }
+
+        closest_hit
+    }
+
+    fn bounding_box(&self) -> &AxisAlignedBoundingBox {
+        &self.bounding_box
+    }
+
+    fn pdf_value(&self, origin: DVec3, direction: DVec3) -> f64 {
+        self.sides
+            .iter()
+            .map(|side| side.pdf_value(origin, direction))
+            .sum::<f64>()
+            / self.sides.len() as f64
+    }
+
+    fn random(&self, origin: DVec3) -> DVec3 {
+        let index = rand::thread_rng().gen_range(0..self.sides.len());
+
+        self.sides[index].random(origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hittable::assert_light_pdf, material::lambertian::Lambertian};
+
+    #[test]
+    fn cuboid() {
+        let material = Arc::new(Lambertian::with_solid(DVec3::ONE));
+        let cuboid = Cuboid::new(
+            DVec3::new(1.0, 2.0, 3.0),
+            DVec3::new(-1.0, 0.0, 1.0),
+            material,
+        );
+
+        let bounding_box = cuboid.bounding_box();
+        assert!(bounding_box
+            .min()
+            .abs_diff_eq(DVec3::new(-1.0, 0.0, 1.0), 1e-3));
+        assert!(bounding_box
+            .max()
+            .abs_diff_eq(DVec3::new(1.0, 2.0, 3.0), 1e-3));
+
+        for (origin, normal) in [
+            (DVec3::new(5.0, 1.0, 2.0), DVec3::X),
+            (DVec3::new(0.0, -4.0, 2.0), DVec3::NEG_Y),
+            (DVec3::new(0.0, 1.0, 7.0), DVec3::Z),
+        ] {
+            let ray = Ray::new(origin, DVec3::new(0.0, 1.0, 2.0) - origin);
+            let hit = cuboid.hit(ray, 0.001..f64::INFINITY).unwrap();
+
+            assert!(hit.front_face);
+            assert_eq!(hit.normal, normal);
+            assert!((hit.t - 0.8).abs() < 1e-9, "{}", hit.t);
+        }
+
+        let inside = Ray::new(DVec3::new(0.0, 1.0, 2.0), DVec3::Y);
+        let hit = cuboid.hit(inside, 0.001..f64::INFINITY).unwrap();
+        assert!(!hit.front_face);
+        assert_eq!(hit.normal, DVec3::NEG_Y);
+
+        assert_light_pdf(&cuboid, DVec3::new(3.0, 4.0, -1.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn flat_cuboid() {
+        let material = Arc::new(Lambertian::with_solid(DVec3::ONE));
+        Cuboid::new(DVec3::ZERO, DVec3::new(1.0, 0.0, 1.0), material);
+    }
+}
@@ -0,0 +1,399 @@
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    ops::Range,
+    path::Path,
+    sync::Arc,
+};
+
+use glam::DVec3;
+
+use crate::{
+    cuboid::Cuboid,
+    hittable::{AxisAlignedBoundingBox, HitRecord, Hittable},
+    material::{isotropic::Isotropic, Material},
+    medium::{self, Coefficients},
+    ray::Ray,
+    texture::{PerlinNoise, Texture},
+};
+
+/// Smoke, clouds or other media whose density changes from point to point inside `boundary`.
+///
+/// Free flight distances are sampled with delta tracking against the largest density of the
+/// field, so the estimate stays unbiased however uneven the medium is.
+#[derive(Debug)]
+pub struct HeterogeneousMedium {
+    boundary: Arc<dyn Hittable>,
+    density: Density,
+    max_density: f64,
+    coefficients: Coefficients,
+    phase_function: Arc<dyn Material>,
+}
+
+/// Where the density of a [`HeterogeneousMedium`] comes from.
+#[derive(Debug)]
+pub enum Density {
+    Grid(DensityGrid),
+    Noise(NoiseDensity),
+}
+
+impl Density {
+    pub fn at(&self, position: DVec3) -> f64 {
+        match self {
+            Density::Grid(grid) => grid.at(position),
+            Density::Noise(noise) => noise.at(position),
+        }
+    }
+
+    /// An upper bound of the density anywhere, used as the majorant while tracking.
+    pub fn max(&self) -> f64 {
+        match self {
+            Density::Grid(grid) => grid.max(),
+            Density::Noise(noise) => noise.density,
+        }
+    }
+}
+
+impl HeterogeneousMedium {
+    pub fn new(boundary: Arc<dyn Hittable>, density: Density, albedo: Texture) -> Self {
+        let max_density = density.max();
+
+        Self {
+            boundary,
+            density,
+            max_density,
+            coefficients: Coefficients::GRAY,
+            phase_function: Arc::new(Isotropic::new(albedo)),
+        }
+    }
+
+    /// Fills the bounds of `grid`.
+    pub fn from_grid(grid: DensityGrid, albedo: Texture) -> Self {
+        let phase_function: Arc<dyn Material> = Arc::new(Isotropic::new(albedo));
+        let boundary = Arc::new(Cuboid::new(
+            grid.bounds.min(),
+            grid.bounds.max(),
+            phase_function.clone(),
+        ));
+        let density = Density::Grid(grid);
+
+        Self {
+            boundary,
+            max_density: density.max(),
+            density,
+            coefficients: Coefficients::GRAY,
+            phase_function,
+        }
+    }
+
+    /// Per channel [`Coefficients`] the density is multiplied with.
+    pub fn with_coefficients(mut self, sigma_a: DVec3, sigma_s: DVec3) -> Self {
+        self.coefficients = Coefficients { sigma_a, sigma_s };
+        self
+    }
+
+    /// See [phase functions](crate::medium#phase-functions).
+    pub fn with_phase_function(mut self, phase_function: Arc<dyn Material>) -> Self {
+        self.phase_function = phase_function;
+        self
+    }
+
+    /// Estimates the fraction of light that passes along `ray` through `t_range` with ratio
+    /// tracking, independently of the delta tracking in [`Hittable::hit`].
+    #[cfg(test)]
+    fn transmittance(&self, ray: Ray, t_range: Range<f64>) -> DVec3 {
+        let Some(span) = medium::boundary_span(self.boundary.as_ref(), ray, t_range) else {
+            return DVec3::ONE;
+        };
+
+        medium::transmittance(ray, span, self.coefficients, self.max_density, |point| {
+            self.density.at(point)
+        })
+    }
+}
+
+impl Hittable for HeterogeneousMedium {
+    fn hit(&self, ray: Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let span = medium::boundary_span(self.boundary.as_ref(), ray, t_range)?;
+
+        medium::track(
+            ray,
+            span,
+            self.coefficients,
+            self.max_density,
+            |point| self.density.at(point),
+            &self.phase_function,
+        )
+    }
+
+    fn bounding_box(&self) -> &AxisAlignedBoundingBox {
+        self.boundary.bounding_box()
+    }
+
+    fn pdf_value(&self, origin: DVec3, direction: DVec3) -> f64 {
+        self.boundary.pdf_value(origin, direction)
+    }
+
+    fn random(&self, origin: DVec3) -> DVec3 {
+        self.boundary.random(origin)
+    }
+}
+
+/// A dense grid of densities stretched over axis aligned world bounds and interpolated
+/// trilinearly between voxel centers.
+#[derive(Debug)]
+pub struct DensityGrid {
+    resolution: [usize; 3],
+    data: Vec<f32>,
+    bounds: AxisAlignedBoundingBox,
+    scale: f64,
+    max: f64,
+}
+
+impl DensityGrid {
+    /// `data` holds `resolution[0] * resolution[1] * resolution[2]` values with `x` varying
+    /// fastest, then `y`, then `z`.
+    pub fn new(resolution: [usize; 3], data: Vec<f32>, corner0: DVec3, corner1: DVec3) -> Self {
+        assert_eq!(
+            data.len(),
+            resolution.iter().product::<usize>(),
+            "density grid data doesn't match its resolution"
+        );
+        assert!(
+            resolution.iter().all(|&size| size > 0),
+            "density grid is empty"
+        );
+
+        let max = data
+            .iter()
+            .fold(0.0f64, |max, &value| max.max(value as f64));
+
+        Self {
+            resolution,
+            data,
+            bounds: AxisAlignedBoundingBox::from_corners(corner0, corner1),
+            scale: 1.0,
+            max,
+        }
+    }
+
+    /// Reads a raw little endian grid: the resolution as three `u32`, the minimum and maximum
+    /// corner as six `f32`, then the `f32` densities in the order of [`DensityGrid::new`].
+    pub fn open(file: &Path) -> io::Result<Self> {
+        let bytes = fs::read(file)?;
+        let invalid = |message: &str| io::Error::new(ErrorKind::InvalidData, message.to_owned());
+
+        let mut words = bytes
+            .chunks_exact(4)
+            .map(|word| [word[0], word[1], word[2], word[3]]);
+        let mut next_word = || {
+            words
+                .next()
+                .ok_or_else(|| invalid("density grid is truncated"))
+        };
+
+        let mut resolution = [0; 3];
+        for size in &mut resolution {
+            *size = u32::from_le_bytes(next_word()?) as usize;
+        }
+
+        let mut corners = [0.0; 6];
+        for corner in &mut corners {
+            *corner = f32::from_le_bytes(next_word()?) as f64;
+        }
+
+        let count = resolution
+            .iter()
+            .try_fold(1usize, |count, &size| count.checked_mul(size))
+            .ok_or_else(|| invalid("density grid resolution is too large"))?;
+        if count == 0 {
+            return Err(invalid("density grid is empty"));
+        }
+        let size = count
+            .checked_add(9)
+            .and_then(|words| words.checked_mul(4))
+            .ok_or_else(|| invalid("density grid resolution is too large"))?;
+        if bytes.len() != size {
+            return Err(invalid("density grid size doesn't match its resolution"));
+        }
+
+        let data = (0..count)
+            .map(|_| next_word().map(f32::from_le_bytes))
+            .collect::<io::Result<Vec<f32>>>()?;
+
+        Ok(Self::new(
+            resolution,
+            data,
+            DVec3::new(corners[0], corners[1], corners[2]),
+            DVec3::new(corners[3], corners[4], corners[5]),
+        ))
+    }
+
+    /// Multiplies every density, handy for grids stored normalized to 0..1.
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn bounds(&self) -> &AxisAlignedBoundingBox {
+        &self.bounds
+    }
+
+    pub fn at(&self, position: DVec3) -> f64 {
+        let min = self.bounds.min();
+        let size = self.bounds.max() - min;
+        let relative = (position - min) / size;
+
+        if relative.cmplt(DVec3::ZERO).any() || relative.cmpgt(DVec3::ONE).any() {
+            return 0.0;
+        }
+
+        let resolution = DVec3::new(
+            self.resolution[0] as f64,
+            self.resolution[1] as f64,
+            self.resolution[2] as f64,
+        );
+        let voxel = (relative * resolution - 0.5).max(DVec3::ZERO);
+        let base = voxel.floor();
+        let fraction = voxel - base;
+
+        let mut density = 0.0;
+
+        for corner in 0..8 {
+            let offset = [corner & 1, (corner >> 1) & 1, (corner >> 2) & 1];
+            let mut weight = 1.0;
+            let mut index = [0; 3];
+
+            for axis in 0..3 {
+                index[axis] = (base[axis] as usize + offset[axis]).min(self.resolution[axis] - 1);
+                weight *= if offset[axis] == 1 {
+                    fraction[axis]
+                } else {
+                    1.0 - fraction[axis]
+                };
+            }
+
+            density += weight * self.voxel(index);
+        }
+
+        density * self.scale
+    }
+
+    fn voxel(&self, [x, y, z]: [usize; 3]) -> f64 {
+        self.data[x + self.resolution[0] * (y + self.resolution[1] * z)] as f64
+    }
+
+    fn max(&self) -> f64 {
+        self.max * self.scale
+    }
+}
+
+/// Procedural cloud density from Perlin turbulence, clamped to `0..density`.
+#[derive(Debug)]
+pub struct NoiseDensity {
+    perlin: PerlinNoise,
+    density: f64,
+    frequency: f64,
+    octaves: u32,
+}
+
+impl NoiseDensity {
+    pub fn new(density: f64, frequency: f64) -> Self {
+        Self {
+            perlin: PerlinNoise::new(),
+            density,
+            frequency,
+            octaves: 7,
+        }
+    }
+
+    pub fn with_octaves(mut self, octaves: u32) -> Self {
+        self.octaves = octaves;
+        self
+    }
+
+    pub fn at(&self, position: DVec3) -> f64 {
+        let turbulence = self
+            .perlin
+            .turbulence(position * self.frequency, self.octaves);
+
+        self.density * turbulence.min(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    #[test]
+    fn density_grid() {
+        let grid = DensityGrid::new(
+            [2, 1, 1],
+            vec![0.0, 4.0],
+            DVec3::ZERO,
+            DVec3::new(2.0, 1.0, 1.0),
+        );
+
+        assert_eq!(grid.at(DVec3::new(0.5, 0.5, 0.5)), 0.0);
+        assert_eq!(grid.at(DVec3::new(1.0, 0.5, 0.5)), 2.0);
+        assert_eq!(grid.at(DVec3::new(1.9, 0.1, 0.9)), 4.0);
+        assert_eq!(grid.at(DVec3::new(3.0, 0.5, 0.5)), 0.0);
+
+        let mut bytes = Vec::new();
+        for size in [2u32, 1, 1] {
+            bytes.extend(size.to_le_bytes());
+        }
+        for value in [0.0f32, 0.0, 0.0, 2.0, 1.0, 1.0, 0.0, 4.0] {
+            bytes.extend(value.to_le_bytes());
+        }
+
+        let directory = env::temp_dir().join(format!("rust_tracer_{}_density_grid", process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("density.raw");
+        fs::write(&path, &bytes).unwrap();
+        let loaded = DensityGrid::open(&path).unwrap().with_scale(0.5);
+        assert_eq!(loaded.at(DVec3::new(1.0, 0.5, 0.5)), 1.0);
+        assert_eq!(loaded.max(), 2.0);
+
+        fs::write(&path, &bytes[..bytes.len() - 4]).unwrap();
+        assert!(DensityGrid::open(&path).is_err());
+
+        // A header whose voxel count overflows is rejected rather than wrapping around.
+        let mut huge = bytes.clone();
+        for size in huge[..12].chunks_exact_mut(4) {
+            size.copy_from_slice(&u32::MAX.to_le_bytes());
+        }
+        fs::write(&path, &huge).unwrap();
+        let error = DensityGrid::open(&path).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn tracking_matches_beer_lambert() {
+        let density: f64 = 0.5;
+        let grid = DensityGrid::new([1, 1, 1], vec![density as f32], DVec3::ZERO, DVec3::ONE);
+        let medium = HeterogeneousMedium::from_grid(grid, Texture::Solid(DVec3::ONE));
+        let ray = Ray::new(DVec3::new(0.5, 0.5, -1.0), DVec3::Z);
+
+        let samples = 20000;
+        let escaped = (0..samples)
+            .filter(|_| medium.hit(ray, 0.001..f64::INFINITY).is_none())
+            .count() as f64
+            / samples as f64;
+        let transmittance = (0..samples)
+            .map(|_| medium.transmittance(ray, 0.001..f64::INFINITY))
+            .sum::<DVec3>()
+            / samples as f64;
+
+        let expected = (-density).exp();
+        assert!((escaped - expected).abs() < 0.02, "{escaped}");
+        assert!(
+            transmittance.abs_diff_eq(DVec3::splat(expected), 0.02),
+            "{transmittance}"
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use std::{cmp::Ordering, fmt::Debug, ops::Range, sync::Arc};
 
 use glam::{DVec2, DVec3};
+use rand::Rng;
 
 use crate::{material::Material, ray::Ray};
 
@@ -8,6 +9,30 @@ pub trait Hittable: Send + Sync + Debug {
     fn hit(&self, ray: Ray, t_range: Range<f64>) -> Option<HitRecord>;
 
     fn bounding_box(&self) -> &AxisAlignedBoundingBox;
+
+    /// Solid angle density of sampling `direction` from `origin` with [`Hittable::random`].
+    fn pdf_value(&self, origin: DVec3, direction: DVec3) -> f64;
+
+    /// A random direction from `origin` towards the surface, used for sampling lights.
+    fn random(&self, origin: DVec3) -> DVec3;
+}
+
+impl<T: Hittable + ?Sized> Hittable for Box<T> {
+    fn hit(&self, ray: Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        self.as_ref().hit(ray, t_range)
+    }
+
+    fn bounding_box(&self) -> &AxisAlignedBoundingBox {
+        self.as_ref().bounding_box()
+    }
+
+    fn pdf_value(&self, origin: DVec3, direction: DVec3) -> f64 {
+        self.as_ref().pdf_value(origin, direction)
+    }
+
+    fn random(&self, origin: DVec3) -> DVec3 {
+        self.as_ref().random(origin)
+    }
 }
 
 #[derive(Clone)]
@@ -77,6 +102,44 @@ impl AxisAlignedBoundingBox {
         }
     }
 
+    pub fn min(&self) -> DVec3 {
+        DVec3::new(self.x.start, self.y.start, self.z.start)
+    }
+
+    pub fn max(&self) -> DVec3 {
+        DVec3::new(self.x.end, self.y.end, self.z.end)
+    }
+
+    pub fn centroid(&self) -> DVec3 {
+        0.5 * (self.min() + self.max())
+    }
+
+    pub fn surface_area(&self) -> f64 {
+        let extent = (self.max() - self.min()).max(DVec3::ZERO);
+
+        2.0 * (extent.x * extent.y + extent.y * extent.z + extent.z * extent.x)
+    }
+
+    pub fn longest_axis(&self) -> usize {
+        let extent = self.max() - self.min();
+
+        if extent.x > extent.y && extent.x > extent.z {
+            0
+        } else if extent.y > extent.z {
+            1
+        } else {
+            2
+        }
+    }
+
+    pub fn padded(self) -> Self {
+        Self {
+            x: pad(self.x),
+            y: pad(self.y),
+            z: pad(self.z),
+        }
+    }
+
     pub fn compare_x(box0: &Self, box1: &Self) -> Ordering {
         box0.x.start.total_cmp(&box1.x.start)
     }
@@ -177,18 +240,86 @@ impl Hittable for HittableList {
     fn bounding_box(&self) -> &AxisAlignedBoundingBox {
         &self.bounding_box
     }
+
+    fn pdf_value(&self, origin: DVec3, direction: DVec3) -> f64 {
+        if self.objects.is_empty() {
+            return 0.0;
+        }
+
+        let weight = (self.objects.len() as f64).recip();
+
+        self.objects
+            .iter()
+            .map(|object| weight * object.pdf_value(origin, direction))
+            .sum()
+    }
+
+    fn random(&self, origin: DVec3) -> DVec3 {
+        // Any direction will do, an empty list has a density of zero everywhere.
+        if self.objects.is_empty() {
+            return DVec3::X;
+        }
+
+        let index = rand::thread_rng().gen_range(0..self.objects.len());
+
+        self.objects[index].random(origin)
+    }
 }
 
-#[allow(dead_code)]
 fn expand(delta: f64, range: Range<f64>) -> Range<f64> {
     let padding = delta / 2.0;
     (range.start - padding)..(range.end + padding)
 }
 
+fn pad(range: Range<f64>) -> Range<f64> {
+    const DELTA: f64 = 0.0001;
+
+    if range.end - range.start < DELTA {
+        expand(DELTA, range)
+    } else {
+        range
+    }
+}
+
 fn combined(range0: Range<f64>, range1: Range<f64>) -> Range<f64> {
     range0.start.min(range1.start)..(range0.end.max(range1.end))
 }
 
+/// Checks that [`Hittable::pdf_value`] is a density over all directions from `origin` and
+/// that [`Hittable::random`] follows it.
+#[cfg(test)]
+pub(crate) fn assert_light_pdf(light: &dyn Hittable, origin: DVec3) {
+    use crate::material::microfacet::integrate_directions;
+
+    let over_sphere = |function: &dyn Fn(DVec3) -> f64| {
+        integrate_directions(0.0..1.0, function) + integrate_directions(-1.0..0.0, function)
+    };
+
+    let total = over_sphere(&|direction| light.pdf_value(origin, direction));
+    assert!((total - 1.0).abs() < 1e-2, "{total}");
+
+    // Weighting samples by their inverse density measures the solid angle they cover.
+    let solid_angle = over_sphere(&|direction| {
+        if light.pdf_value(origin, direction) > 0.0 {
+            1.0
+        } else {
+            0.0
+        }
+    });
+
+    let samples = 20000;
+    let mut sampled_solid_angle = 0.0;
+    for _ in 0..samples {
+        let pdf = light.pdf_value(origin, light.random(origin));
+        assert!(pdf > 0.0);
+        sampled_solid_angle += pdf.recip() / samples as f64;
+    }
+    assert!(
+        (sampled_solid_angle / solid_angle - 1.0).abs() < 0.02,
+        "{sampled_solid_angle} {solid_angle}"
+    );
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
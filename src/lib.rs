@@ -1,10 +1,19 @@
+pub mod background;
 pub mod bounding_volume;
 pub mod camera;
+pub mod color;
+pub mod constant_medium;
+pub mod cuboid;
+pub mod heterogeneous_medium;
 pub mod hittable;
 pub mod material;
+pub mod medium;
+pub mod obj;
 pub mod prelude;
+pub mod quad;
 pub(crate) mod ray;
+pub mod spectrum;
 pub mod sphere;
 pub mod texture;
-
-const IMAGES_FOLDER: &str = "images";
+pub mod transform;
+pub mod triangle;
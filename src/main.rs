@@ -53,8 +53,8 @@ fn main() {
 
     camera.render_image_with_progress(&world);
 
-    match camera.save_image("image") {
+    match camera.save_image("images/image.png") {
         Ok(_) => println!("Image saved successfully!"),
-        Err(_) => println!("Failed to save the image!"),
+        Err(error) => println!("Failed to save the image: {error}"),
     }
 }
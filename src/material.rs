@@ -1,19 +1,64 @@
 use std::fmt::Debug;
 
-use glam::DVec3;
+use glam::{DVec2, DVec3};
 
-use crate::{hittable::HitRecord, ray::Ray};
+use crate::{hittable::HitRecord, ray::Ray, spectrum::SampledWavelengths};
 
+pub mod conductor;
 pub mod dielectric;
+pub mod diffuse_light;
+pub mod henyey_greenstein;
+pub mod isotropic;
 pub mod lambertian;
+pub mod layered;
 pub mod metal;
+pub mod microfacet;
+pub mod mix;
+pub mod oren_nayar;
+pub mod principled;
+pub mod rough_dielectric;
 pub mod util;
 
 pub trait Material: Send + Sync + Debug {
     fn scatter(&self, ray: Ray, hit_record: HitRecord) -> Option<Scattered>;
+
+    /// Radiance given off at `point`, in the channels of `wavelengths`.
+    fn emitted(&self, _uv: DVec2, _point: DVec3, _wavelengths: SampledWavelengths) -> DVec3 {
+        DVec3::ZERO
+    }
+
+    /// Solid angle density with which [`Material::scatter`] picks `direction`.
+    fn scattering_pdf(&self, _ray: Ray, _hit_record: &HitRecord, _direction: DVec3) -> f64 {
+        0.0
+    }
+
+    /// The BSDF times the cosine term for light arriving from `direction`.
+    ///
+    /// Only materials that report a [`Scattered::pdf`] need to implement this, it is used to
+    /// weight directions that were not chosen by [`Material::scatter`] like light samples.
+    /// Specular scattering is left out.
+    fn evaluate(&self, _ray: Ray, _hit_record: &HitRecord, _direction: DVec3) -> DVec3 {
+        DVec3::ZERO
+    }
 }
 
 pub struct Scattered {
+    /// In the channels of the wavelengths the incoming ray carries.
     pub attenuation: DVec3,
     pub direction: Ray,
+    /// Density of the sampled direction, `None` for perfectly specular scattering.
+    pub pdf: Option<f64>,
+}
+
+/// A hit at the origin on a surface facing up along Y, for testing materials.
+#[cfg(test)]
+pub(crate) fn surface_hit(material: std::sync::Arc<dyn Material>, front_face: bool) -> HitRecord {
+    HitRecord {
+        point: DVec3::ZERO,
+        normal: DVec3::Y,
+        t: 1.0,
+        uv: DVec2::ZERO,
+        material,
+        front_face,
+    }
 }
@@ -0,0 +1,238 @@
+use glam::{DVec2, DVec3};
+use rand::Rng;
+
+use crate::{
+    hittable::HitRecord, prelude::Texture, ray::Ray, spectrum::SampledWavelengths, texture::Sample,
+};
+
+use super::{
+    microfacet::{Frame, TrowbridgeReitz},
+    util::{fresnel_conductor, reflect},
+    Material, Scattered,
+};
+
+/// Complex refraction index `eta + i k` of a conductor, sampled at red, green and blue.
+#[derive(Debug, Clone, Copy)]
+pub struct ComplexIor {
+    pub eta: DVec3,
+    pub k: DVec3,
+}
+
+impl ComplexIor {
+    pub const GOLD: Self = Self {
+        eta: DVec3::new(0.143119, 0.374957, 1.44248),
+        k: DVec3::new(3.98316, 2.38572, 1.60322),
+    };
+    pub const COPPER: Self = Self {
+        eta: DVec3::new(0.200438, 0.924033, 1.10221),
+        k: DVec3::new(3.91295, 2.45285, 2.14219),
+    };
+    pub const ALUMINIUM: Self = Self {
+        eta: DVec3::new(1.65746, 0.880369, 0.521229),
+        k: DVec3::new(9.22387, 6.26952, 4.837),
+    };
+    pub const SILVER: Self = Self {
+        eta: DVec3::new(0.155265, 0.116723, 0.138342),
+        k: DVec3::new(4.82835, 3.12225, 2.14696),
+    };
+
+    /// Roughly the dominant wavelengths of the sRGB primaries in nanometers, where the red,
+    /// green and blue samples are taken to lie.
+    const WAVELENGTHS: [f64; 3] = [611.0, 549.0, 464.0];
+
+    /// The index in the channels of `wavelengths`. Spectral rays interpolate linearly between
+    /// the samples and hold the outer ones constant beyond, rather than upsampling them like a
+    /// reflectance.
+    pub fn at(&self, wavelengths: SampledWavelengths) -> Self {
+        let SampledWavelengths::Spectral(wavelengths) = wavelengths else {
+            return *self;
+        };
+
+        let [red, green, blue] = Self::WAVELENGTHS;
+        let interpolate = |samples: DVec3| {
+            DVec3::from_array(wavelengths.to_array().map(|wavelength| {
+                if wavelength >= green {
+                    let fraction = ((wavelength - green) / (red - green)).min(1.0);
+
+                    samples.y + (samples.x - samples.y) * fraction
+                } else {
+                    let fraction = ((green - wavelength) / (green - blue)).min(1.0);
+
+                    samples.y + (samples.z - samples.y) * fraction
+                }
+            }))
+        };
+
+        Self {
+            eta: interpolate(self.eta),
+            k: interpolate(self.k),
+        }
+    }
+}
+
+/// A metal with a GGX microfacet distribution, sampled through its visible normals.
+///
+/// The roughness comes from the first channel of a texture and goes from 0 for a mirror to 1.
+#[derive(Debug)]
+pub struct Conductor {
+    ior: ComplexIor,
+    roughness: Texture,
+}
+
+impl Conductor {
+    pub fn new(ior: ComplexIor, roughness: Texture) -> Self {
+        Self { ior, roughness }
+    }
+
+    pub fn with_roughness(ior: ComplexIor, roughness: f64) -> Self {
+        Self::new(ior, Texture::Solid(DVec3::splat(roughness)))
+    }
+
+    fn distribution(&self, hit_record: &HitRecord) -> TrowbridgeReitz {
+        TrowbridgeReitz::from_roughness(self.roughness.sample(hit_record.uv, hit_record.point).x)
+    }
+}
+
+impl Material for Conductor {
+    fn scatter(&self, ray: Ray, hit_record: HitRecord) -> Option<Scattered> {
+        let frame = Frame::new(hit_record.normal);
+        let outgoing = frame.to_local(-ray.direction.normalize());
+
+        if outgoing.z <= 0.0 {
+            return None;
+        }
+
+        let distribution = self.distribution(&hit_record);
+        let ior = self.ior.at(ray.wavelengths);
+
+        if distribution.is_smooth() {
+            let direction = reflect(ray.direction.normalize(), hit_record.normal);
+
+            return Some(Scattered {
+                attenuation: fresnel_conductor(outgoing.z, ior.eta, ior.k),
+                direction: ray.spawn(hit_record.point, direction),
+                pdf: None,
+            });
+        }
+
+        let mut rng = rand::thread_rng();
+        let microfacet_normal =
+            distribution.sample_visible(outgoing, DVec2::new(rng.gen(), rng.gen()));
+        let incoming = reflect(-outgoing, microfacet_normal);
+
+        if incoming.z <= 0.0 {
+            return None;
+        }
+
+        let cos_theta = outgoing.dot(microfacet_normal);
+        let pdf = distribution.visible_pdf(outgoing, microfacet_normal) / (4.0 * cos_theta);
+
+        // The BSDF times the cosine over the pdf, most of the terms cancel out.
+        let attenuation = fresnel_conductor(cos_theta, ior.eta, ior.k)
+            * distribution.g(outgoing, incoming)
+            / distribution.g1(outgoing);
+
+        Some(Scattered {
+            attenuation,
+            direction: ray.spawn(hit_record.point, frame.to_world(incoming)),
+            pdf: Some(pdf),
+        })
+    }
+
+    fn scattering_pdf(&self, ray: Ray, hit_record: &HitRecord, direction: DVec3) -> f64 {
+        let frame = Frame::new(hit_record.normal);
+        let outgoing = frame.to_local(-ray.direction.normalize());
+        let incoming = frame.to_local(direction.normalize());
+
+        if outgoing.z <= 0.0 || incoming.z <= 0.0 {
+            return 0.0;
+        }
+
+        let distribution = self.distribution(hit_record);
+        let microfacet_normal = (outgoing + incoming).normalize();
+
+        distribution.visible_pdf(outgoing, microfacet_normal)
+            / (4.0 * outgoing.dot(microfacet_normal))
+    }
+
+    fn evaluate(&self, ray: Ray, hit_record: &HitRecord, direction: DVec3) -> DVec3 {
+        let frame = Frame::new(hit_record.normal);
+        let outgoing = frame.to_local(-ray.direction.normalize());
+        let incoming = frame.to_local(direction.normalize());
+
+        if outgoing.z <= 0.0 || incoming.z <= 0.0 {
+            return DVec3::ZERO;
+        }
+
+        let distribution = self.distribution(hit_record);
+
+        if distribution.is_smooth() {
+            return DVec3::ZERO;
+        }
+
+        let microfacet_normal = (outgoing + incoming).normalize();
+        let ior = self.ior.at(ray.wavelengths);
+        let fresnel = fresnel_conductor(outgoing.dot(microfacet_normal), ior.eta, ior.k);
+
+        fresnel * distribution.d(microfacet_normal) * distribution.g(outgoing, incoming)
+            / (4.0 * outgoing.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::material::{microfacet::integrate_directions, surface_hit};
+
+    #[test]
+    fn conductor() {
+        let reflectance = fresnel_conductor(1.0, ComplexIor::GOLD.eta, ComplexIor::GOLD.k);
+        assert!(reflectance.x > reflectance.y && reflectance.y > reflectance.z);
+        assert!(reflectance.x > 0.9);
+
+        // Spectral rays at the sample wavelengths see the RGB index, in between it blends.
+        let gold = ComplexIor::GOLD.at(SampledWavelengths::Spectral(DVec3::new(
+            611.0, 549.0, 464.0,
+        )));
+        assert!(gold.eta.abs_diff_eq(ComplexIor::GOLD.eta, 1e-12));
+        assert!(gold.k.abs_diff_eq(ComplexIor::GOLD.k, 1e-12));
+        let gold = ComplexIor::GOLD.at(SampledWavelengths::Spectral(DVec3::new(
+            700.0, 580.0, 400.0,
+        )));
+        assert_eq!(gold.eta.x, ComplexIor::GOLD.eta.x);
+        assert!(gold.eta.y > ComplexIor::GOLD.eta.x && gold.eta.y < ComplexIor::GOLD.eta.y);
+        assert_eq!(gold.k.z, ComplexIor::GOLD.k.z);
+
+        let material = Arc::new(Conductor::with_roughness(ComplexIor::ALUMINIUM, 0.5));
+        let hit_record = surface_hit(material.clone(), true);
+        let ray = Ray::new(DVec3::new(-1.0, 1.0, 0.0), DVec3::new(1.0, -1.0, 0.0));
+
+        // The sampled throughput matches the BSDF integrated over the hemisphere.
+        let samples = 100000;
+        let mut sampled = DVec3::ZERO;
+
+        for _ in 0..samples {
+            if let Some(scattered) = material.scatter(ray, hit_record.clone()) {
+                let direction = scattered.direction.direction;
+                let pdf = material.scattering_pdf(ray, &hit_record, direction);
+
+                assert!((scattered.pdf.unwrap() - pdf).abs() < 1e-6 * pdf);
+                sampled += scattered.attenuation;
+            }
+        }
+
+        let sampled = sampled / samples as f64;
+        let integrated = integrate_directions(0.0..1.0, |direction| {
+            let direction = DVec3::new(direction.x, direction.z, direction.y);
+
+            material.evaluate(ray, &hit_record, direction)
+        });
+        assert!(
+            sampled.abs_diff_eq(integrated, 0.01),
+            "{sampled} {integrated}"
+        );
+        assert!(sampled.max_element() < 1.0);
+    }
+}
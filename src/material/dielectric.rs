@@ -1,46 +1,300 @@
 use glam::DVec3;
+use rand::Rng;
 
 use crate::{hittable::HitRecord, material::util::refract, ray::Ray};
 
-use super::{util::reflect, Material, Scattered};
+use super::{
+    util::{fresnel_dielectric, fresnel_schlick, reflect},
+    Material, Scattered,
+};
+
+/// How [`Dielectric`] splits light between reflection and refraction.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Fresnel {
+    /// The exact unpolarized Fresnel equations.
+    #[default]
+    Exact,
+    /// Schlick's cheaper approximation.
+    Schlick,
+}
+
+impl Fresnel {
+    /// Fraction of the light that is reflected, `eta` is the refraction index of the far side
+    /// over the near side.
+    pub fn reflectance(&self, cos_theta: f64, eta: f64) -> f64 {
+        match self {
+            Fresnel::Exact => fresnel_dielectric(cos_theta, eta),
+            Fresnel::Schlick => fresnel_schlick(cos_theta, eta),
+        }
+    }
+}
+
+/// How the refraction index changes with the wavelength, which splits white light into a
+/// rainbow when rendering spectrally.
+#[derive(Debug, Clone, Copy)]
+pub enum Dispersion {
+    /// `n = a + b / λ²` with the wavelength `λ` in micrometers.
+    Cauchy { a: f64, b: f64 },
+    /// `n² = 1 + Σ bᵢ λ² / (λ² - cᵢ)` with the wavelength `λ` in micrometers.
+    Sellmeier { b: [f64; 3], c: [f64; 3] },
+}
+
+impl Dispersion {
+    /// Schott N-BK7 crown glass.
+    pub const BK7: Self = Dispersion::Sellmeier {
+        b: [1.03961212, 0.231792344, 1.01046945],
+        c: [0.00600069867, 0.0200179144, 103.560653],
+    };
+    /// Malitson's fit for fused silica.
+    pub const FUSED_SILICA: Self = Dispersion::Sellmeier {
+        b: [0.6961663, 0.4079426, 0.8974794],
+        c: [0.004679148, 0.01351206, 97.934],
+    };
+    pub const DIAMOND: Self = Dispersion::Sellmeier {
+        b: [0.3306, 4.3356, 0.0],
+        c: [0.030625, 0.011236, 0.0],
+    };
+
+    /// The refraction index at `wavelength` in nanometers.
+    pub fn refraction_index(&self, wavelength: f64) -> f64 {
+        let micrometers = wavelength * 1e-3;
+        let squared = micrometers * micrometers;
+
+        match self {
+            Dispersion::Cauchy { a, b } => a + b / squared,
+            Dispersion::Sellmeier { b, c } => (1.0
+                + b.iter()
+                    .zip(c)
+                    .map(|(b, c)| b * squared / (squared - c))
+                    .sum::<f64>())
+            .sqrt(),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct Dielectric {
     refraction_index: f64,
+    dispersion: Option<Dispersion>,
+    absorption: DVec3,
+    fresnel: Fresnel,
 }
 
 impl Dielectric {
     pub fn new(refraction_index: f64) -> Self {
-        Self { refraction_index }
+        Self {
+            refraction_index,
+            dispersion: None,
+            absorption: DVec3::ZERO,
+            fresnel: Fresnel::default(),
+        }
     }
-}
 
-impl Material for Dielectric {
-    fn scatter(&self, ray: Ray, hit_record: HitRecord) -> Option<Scattered> {
-        let attenuation = DVec3::splat(1.0);
+    /// A dispersive dielectric, rays without a wavelength use its index at the sodium D line.
+    pub fn from_dispersion(dispersion: Dispersion) -> Self {
+        Self {
+            dispersion: Some(dispersion),
+            ..Self::new(dispersion.refraction_index(587.6))
+        }
+    }
+
+    /// Per channel absorption coefficient of the inside, light travelling a distance `d` through
+    /// it is attenuated by `exp(-absorption * d)` following the Beer-Lambert law. Spectral rays
+    /// upsample it and attenuate every wavelength on its own.
+    pub fn with_absorption(mut self, absorption: DVec3) -> Self {
+        self.absorption = absorption;
+        self
+    }
+
+    pub fn with_fresnel(mut self, fresnel: Fresnel) -> Self {
+        self.fresnel = fresnel;
+        self
+    }
+
+    /// [`Material::scatter`] drawing the choice between reflection and refraction from `rng`.
+    pub fn scatter_with_rng(
+        &self,
+        ray: Ray,
+        hit_record: HitRecord,
+        rng: &mut impl Rng,
+    ) -> Option<Scattered> {
+        // Leaving the object means the ray has just travelled through its inside.
+        let mut attenuation = if hit_record.front_face {
+            DVec3::ONE
+        } else {
+            let absorption = ray.wavelengths.upsample(self.absorption);
+
+            (-absorption * hit_record.t * ray.direction.length()).exp()
+        };
+        let refraction_index = match (self.dispersion, ray.wavelengths.hero()) {
+            (Some(dispersion), Some(wavelength)) => {
+                // Both the direction and the reflectance only hold for the hero wavelength.
+                attenuation *= ray.wavelengths.terminate_secondary();
+
+                dispersion.refraction_index(wavelength)
+            }
+            _ => self.refraction_index,
+        };
         let refraction_ratio = if hit_record.front_face {
-            self.refraction_index.recip()
+            refraction_index.recip()
         } else {
-            self.refraction_index
+            refraction_index
         };
 
         let unit_direction = ray.direction.normalize();
         let cos_theta = (-unit_direction.dot(hit_record.normal)).min(1.0);
-        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+        let reflectance = self
+            .fresnel
+            .reflectance(cos_theta, refraction_ratio.recip());
 
-        let cannot_refract = refraction_ratio * sin_theta > 1.0;
-
-        let refracted = if cannot_refract {
+        // Total internal reflection has a reflectance of one, so it always takes this branch.
+        let scattered = if rng.gen_range(0.0..1.0) < reflectance {
             reflect(unit_direction, hit_record.normal)
         } else {
             refract(unit_direction, hit_record.normal, refraction_ratio)
         };
 
-        let direction = Ray::new_with_time(hit_record.point, refracted, ray.time);
+        let direction = ray.spawn(hit_record.point, scattered);
 
         Some(Scattered {
             attenuation,
             direction,
+            pdf: None,
         })
     }
 }
+
+impl Material for Dielectric {
+    fn scatter(&self, ray: Ray, hit_record: HitRecord) -> Option<Scattered> {
+        self.scatter_with_rng(ray, hit_record, &mut rand::thread_rng())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::{hittable::Hittable, spectrum::SampledWavelengths, sphere::Sphere};
+
+    #[test]
+    fn absorption() {
+        let absorption = DVec3::new(0.1, 0.5, 2.0);
+        let glass = Arc::new(Dielectric::new(1.5).with_absorption(absorption));
+        let sphere = Sphere::stationary(DVec3::ZERO, 1.0, glass.clone());
+
+        let entering = Ray::new(DVec3::new(0.0, 0.0, 5.0), DVec3::new(0.0, 0.0, -2.0));
+        let hit = sphere.hit(entering, 0.001..f64::INFINITY).unwrap();
+        let scattered = glass.scatter(entering, hit).unwrap();
+        assert_eq!(scattered.attenuation, DVec3::ONE);
+
+        let leaving = Ray::new(DVec3::new(0.0, 0.0, 1.0), DVec3::new(0.0, 0.0, -2.0));
+        let hit = sphere.hit(leaving, 0.001..f64::INFINITY).unwrap();
+        let scattered = glass.scatter(leaving, hit).unwrap();
+        assert!(scattered
+            .attenuation
+            .abs_diff_eq((-2.0 * absorption).exp(), 1e-9));
+
+        // Spectral rays are absorbed per wavelength rather than by the splatted RGB attenuation.
+        let wavelengths = SampledWavelengths::stratified(0.1);
+        let spectral = Ray {
+            wavelengths,
+            ..leaving
+        };
+        let hit = sphere.hit(spectral, 0.001..f64::INFINITY).unwrap();
+        let scattered = glass.scatter(spectral, hit).unwrap();
+        let expected = (-2.0 * wavelengths.upsample(absorption)).exp();
+        assert!(scattered.attenuation.abs_diff_eq(expected, 1e-9));
+        assert_eq!(scattered.direction.wavelengths, wavelengths);
+    }
+
+    #[test]
+    fn fresnel_reflectance() {
+        for fresnel in [Fresnel::Exact, Fresnel::Schlick] {
+            assert!((fresnel.reflectance(1.0, 1.5) - 0.04).abs() < 1e-9);
+            assert!((fresnel.reflectance(0.0, 1.5) - 1.0).abs() < 1e-9);
+            // Past the critical angle of about 41.8 degrees going from glass into air.
+            assert_eq!(fresnel.reflectance(0.7, 1.5f64.recip()), 1.0);
+        }
+
+        let exact = Fresnel::Exact.reflectance(0.5, 1.5);
+        let schlick = Fresnel::Schlick.reflectance(0.5, 1.5);
+        assert!((exact - 0.0891).abs() < 1e-3, "{exact}");
+        assert!((exact - schlick).abs() < 0.025);
+    }
+
+    #[test]
+    fn reflection_probability() {
+        let glass = Arc::new(Dielectric::new(1.5));
+        let sphere = Sphere::stationary(DVec3::ZERO, 1.0, glass.clone());
+
+        // Arrives at 60 degrees to the normal, where about 9% of the light is reflected.
+        let direction = DVec3::new(60f64.to_radians().sin(), 0.0, -60f64.to_radians().cos());
+        let ray = Ray::new(DVec3::new(0.0, 0.0, 1.0) - direction, direction);
+        let hit = sphere.hit(ray, 0.001..f64::INFINITY).unwrap();
+        let expected = Fresnel::Exact.reflectance(0.5, 1.5);
+
+        let samples = 100000;
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut reflected = 0;
+        let mut energy = DVec3::ZERO;
+
+        for _ in 0..samples {
+            let scattered = glass.scatter_with_rng(ray, hit.clone(), &mut rng).unwrap();
+
+            if scattered.direction.direction.dot(hit.normal) > 0.0 {
+                reflected += 1;
+            }
+            energy += scattered.attenuation;
+        }
+
+        let fraction = reflected as f64 / samples as f64;
+        assert!((fraction - expected).abs() < 0.005, "{fraction} {expected}");
+        assert_eq!(energy, DVec3::splat(samples as f64));
+
+        let first = glass.scatter_with_rng(ray, hit.clone(), &mut StdRng::seed_from_u64(1));
+        let second = glass.scatter_with_rng(ray, hit, &mut StdRng::seed_from_u64(1));
+        assert_eq!(
+            first.unwrap().direction.direction,
+            second.unwrap().direction.direction
+        );
+    }
+
+    #[test]
+    fn dispersion() {
+        let bk7 = Dispersion::BK7;
+        assert!((bk7.refraction_index(587.6) - 1.5168).abs() < 1e-4);
+        assert!(bk7.refraction_index(450.0) > bk7.refraction_index(650.0));
+        assert!((Dispersion::DIAMOND.refraction_index(587.6) - 2.417).abs() < 2e-3);
+
+        let cauchy = Dispersion::Cauchy { a: 1.5, b: 0.004 };
+        assert!((cauchy.refraction_index(500.0) - 1.516).abs() < 1e-9);
+
+        // Blue light bends more than red light entering the glass at an angle.
+        let glass = Dielectric::from_dispersion(bk7);
+        let sphere = Sphere::stationary(DVec3::ZERO, 1.0, Arc::new(Dielectric::new(1.5)));
+        let direction = DVec3::new(0.5, 0.0, -1.0);
+        let mut ray = Ray::new(DVec3::new(0.0, 0.0, 1.0) - direction, direction);
+        let hit = sphere.hit(ray, 0.001..f64::INFINITY).unwrap();
+
+        let mut refracted = |wavelength| {
+            ray.wavelengths = SampledWavelengths::Hero(wavelength);
+            let scattered = glass
+                .scatter_with_rng(ray, hit.clone(), &mut StdRng::seed_from_u64(3))
+                .unwrap();
+
+            scattered.direction.direction.normalize()
+        };
+        let blue = refracted(450.0);
+        let red = refracted(650.0);
+
+        assert!(blue.x < red.x, "{blue} {red}");
+
+        // Only the hero wavelength of a spectral ray follows the refraction.
+        ray.wavelengths = SampledWavelengths::stratified(0.5);
+        let scattered = glass.scatter(ray, hit).unwrap();
+        assert_eq!(scattered.attenuation, DVec3::new(3.0, 0.0, 0.0));
+    }
+}
@@ -0,0 +1,75 @@
+use glam::{DVec2, DVec3};
+
+use crate::{
+    hittable::HitRecord, prelude::Texture, ray::Ray, spectrum::SampledWavelengths, texture::Sample,
+};
+
+use super::{Material, Scattered};
+
+#[derive(Debug)]
+pub struct DiffuseLight {
+    emit: Texture,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: Texture) -> Self {
+        Self { emit }
+    }
+
+    pub fn with_solid(emit: DVec3) -> Self {
+        Self {
+            emit: Texture::Solid(emit),
+        }
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(&self, _ray: Ray, _hit_record: HitRecord) -> Option<Scattered> {
+        None
+    }
+
+    /// Spectral rays upsample the emission as an illuminant.
+    fn emitted(&self, uv: DVec2, point: DVec3, wavelengths: SampledWavelengths) -> DVec3 {
+        wavelengths.upsample_illuminant(self.emit.sample(uv, point))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{material::surface_hit, texture::Checker};
+
+    #[test]
+    fn diffuse_light() {
+        let light = DiffuseLight::new(Texture::Checker(Checker::with_solid(
+            1.0,
+            DVec3::splat(4.0),
+            DVec3::new(1.0, 2.0, 3.0),
+        )));
+
+        assert_eq!(
+            light.emitted(
+                DVec2::ZERO,
+                DVec3::new(0.5, 0.5, 0.5),
+                SampledWavelengths::Rgb
+            ),
+            DVec3::splat(4.0)
+        );
+        assert_eq!(
+            light.emitted(
+                DVec2::ZERO,
+                DVec3::new(1.5, 0.5, 0.5),
+                SampledWavelengths::Rgb
+            ),
+            DVec3::new(1.0, 2.0, 3.0)
+        );
+
+        let light = Arc::new(light);
+        let ray = Ray::new(DVec3::Y, DVec3::NEG_Y);
+        assert!(light
+            .scatter(ray, surface_hit(light.clone(), true))
+            .is_none());
+    }
+}
@@ -0,0 +1,139 @@
+use std::f64::consts::PI;
+
+use glam::DVec3;
+use rand::Rng;
+
+use crate::{hittable::HitRecord, prelude::Texture, ray::Ray};
+
+use super::{Material, Scattered};
+
+/// Phase function of participating media that prefers scattering forwards or backwards.
+///
+/// The asymmetry `g` is the average cosine between the incoming and scattered direction, from
+/// -1 for pure back scattering over 0 for isotropic to 1 for pure forward scattering.
+#[derive(Debug)]
+pub struct HenyeyGreenstein {
+    albedo: Texture,
+    g: f64,
+}
+
+impl HenyeyGreenstein {
+    pub fn new(albedo: Texture, g: f64) -> Self {
+        Self {
+            albedo,
+            g: g.clamp(-0.999, 0.999),
+        }
+    }
+
+    pub fn with_solid(albedo: DVec3, g: f64) -> Self {
+        Self::new(Texture::Solid(albedo), g)
+    }
+
+    fn phase(&self, cos_theta: f64) -> f64 {
+        let denominator = 1.0 + self.g * self.g - 2.0 * self.g * cos_theta;
+
+        (1.0 - self.g * self.g) / (4.0 * PI * denominator * denominator.max(0.0).sqrt())
+    }
+
+    /// Inverts the cumulative distribution of the cosine to the propagation direction.
+    fn sample_cos_theta(&self, u: f64) -> f64 {
+        if self.g.abs() < 1e-3 {
+            return 1.0 - 2.0 * u;
+        }
+
+        let g = self.g;
+        let term = (1.0 - g * g) / (1.0 - g + 2.0 * g * u);
+
+        ((1.0 + g * g - term * term) / (2.0 * g)).clamp(-1.0, 1.0)
+    }
+}
+
+impl Material for HenyeyGreenstein {
+    fn scatter(&self, ray: Ray, hit_record: HitRecord) -> Option<Scattered> {
+        let mut rng = rand::thread_rng();
+        let forward = ray.direction.normalize();
+        let (tangent, bitangent) = forward.any_orthonormal_pair();
+
+        let cos_theta = self.sample_cos_theta(rng.gen_range(0.0..1.0));
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * rng.gen_range(0.0..1.0);
+
+        let direction = sin_theta * phi.cos() * tangent
+            + sin_theta * phi.sin() * bitangent
+            + cos_theta * forward;
+
+        Some(Scattered {
+            attenuation: self.albedo.sample_spectrum(
+                hit_record.uv,
+                hit_record.point,
+                ray.wavelengths,
+            ),
+            direction: ray.spawn(hit_record.point, direction),
+            pdf: Some(self.phase(cos_theta)),
+        })
+    }
+
+    fn scattering_pdf(&self, ray: Ray, _hit_record: &HitRecord, direction: DVec3) -> f64 {
+        self.phase(ray.direction.normalize().dot(direction.normalize()))
+    }
+
+    fn evaluate(&self, ray: Ray, hit_record: &HitRecord, direction: DVec3) -> DVec3 {
+        self.albedo
+            .sample_spectrum(hit_record.uv, hit_record.point, ray.wavelengths)
+            * self.scattering_pdf(ray, hit_record, direction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use glam::DVec2;
+
+    use super::*;
+
+    #[test]
+    fn henyey_greenstein_sampling() {
+        let ray = Ray::new(DVec3::ZERO, DVec3::new(0.0, 0.0, -2.0));
+
+        for g in [-0.7, 0.0, 0.3, 0.9] {
+            let phase_function = Arc::new(HenyeyGreenstein::with_solid(DVec3::ONE, g));
+            let hit_record = HitRecord {
+                point: DVec3::ZERO,
+                normal: DVec3::X,
+                t: 0.0,
+                uv: DVec2::ZERO,
+                material: phase_function.clone(),
+                front_face: true,
+            };
+
+            let samples = 20000;
+            let mut mean_cosine = 0.0;
+            for _ in 0..samples {
+                let scattered = phase_function.scatter(ray, hit_record.clone()).unwrap();
+                let direction = scattered.direction.direction;
+
+                assert!((direction.length() - 1.0).abs() < 1e-9);
+                assert!(
+                    (scattered.pdf.unwrap()
+                        - phase_function.scattering_pdf(ray, &hit_record, direction))
+                    .abs()
+                        < 1e-9
+                );
+
+                mean_cosine += direction.dot(DVec3::NEG_Z) / samples as f64;
+            }
+            assert!((mean_cosine - g).abs() < 0.02, "{g} {mean_cosine}");
+
+            // The phase function integrates to one over the sphere.
+            let steps = 100000;
+            let integral = (0..steps)
+                .map(|step| {
+                    let cos_theta = -1.0 + 2.0 * (step as f64 + 0.5) / steps as f64;
+                    phase_function.phase(cos_theta) * 2.0 * PI * 2.0 / steps as f64
+                })
+                .sum::<f64>();
+            assert!((integral - 1.0).abs() < 1e-3, "{g} {integral}");
+        }
+    }
+}
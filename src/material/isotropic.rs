@@ -0,0 +1,50 @@
+use std::f64::consts::PI;
+
+use glam::DVec3;
+
+use crate::{hittable::HitRecord, prelude::Texture, ray::Ray};
+
+use super::{util::random_unit_vector, Material, Scattered};
+
+/// Scatters uniformly into all directions, used as the phase function of participating media.
+#[derive(Debug)]
+pub struct Isotropic {
+    albedo: Texture,
+}
+
+impl Isotropic {
+    pub fn new(albedo: Texture) -> Self {
+        Self { albedo }
+    }
+
+    pub fn with_solid(albedo: DVec3) -> Self {
+        Self {
+            albedo: Texture::Solid(albedo),
+        }
+    }
+}
+
+impl Material for Isotropic {
+    fn scatter(&self, ray: Ray, hit_record: HitRecord) -> Option<Scattered> {
+        let direction = ray.spawn(hit_record.point, random_unit_vector());
+        let attenuation =
+            self.albedo
+                .sample_spectrum(hit_record.uv, hit_record.point, ray.wavelengths);
+
+        Some(Scattered {
+            attenuation,
+            direction,
+            pdf: Some((4.0 * PI).recip()),
+        })
+    }
+
+    fn scattering_pdf(&self, _ray: Ray, _hit_record: &HitRecord, _direction: DVec3) -> f64 {
+        (4.0 * PI).recip()
+    }
+
+    fn evaluate(&self, ray: Ray, hit_record: &HitRecord, direction: DVec3) -> DVec3 {
+        self.albedo
+            .sample_spectrum(hit_record.uv, hit_record.point, ray.wavelengths)
+            * self.scattering_pdf(ray, hit_record, direction)
+    }
+}
@@ -1,6 +1,8 @@
+use std::f64::consts::PI;
+
 use glam::DVec3;
 
-use crate::{hittable::HitRecord, prelude::Texture, ray::Ray, texture::Sample};
+use crate::{hittable::HitRecord, prelude::Texture, ray::Ray};
 
 use super::{util::random_unit_vector, Material, Scattered};
 
@@ -29,12 +31,28 @@ impl Material for Lambertian {
             scatter_direction = hit_record.normal;
         }
 
-        let direction = Ray::new_with_time(hit_record.point, scatter_direction, ray.time);
-        let attenuation = self.albedo.sample(hit_record.uv, hit_record.point);
+        let direction = ray.spawn(hit_record.point, scatter_direction);
+        let attenuation =
+            self.albedo
+                .sample_spectrum(hit_record.uv, hit_record.point, ray.wavelengths);
+        let pdf = self.scattering_pdf(ray, &hit_record, scatter_direction);
 
         Some(Scattered {
             attenuation,
             direction,
+            pdf: Some(pdf),
         })
     }
+
+    fn scattering_pdf(&self, _ray: Ray, hit_record: &HitRecord, direction: DVec3) -> f64 {
+        let cosine = hit_record.normal.dot(direction.normalize());
+
+        cosine.max(0.0) / PI
+    }
+
+    fn evaluate(&self, ray: Ray, hit_record: &HitRecord, direction: DVec3) -> DVec3 {
+        self.albedo
+            .sample_spectrum(hit_record.uv, hit_record.point, ray.wavelengths)
+            * self.scattering_pdf(ray, hit_record, direction)
+    }
 }
@@ -0,0 +1,157 @@
+use std::sync::Arc;
+
+use glam::{DVec2, DVec3};
+use rand::Rng;
+
+use crate::{hittable::HitRecord, ray::Ray, spectrum::SampledWavelengths};
+
+use super::{
+    util::{fresnel_dielectric, reflect},
+    Material, Scattered,
+};
+
+/// A smooth dielectric coating like varnish or lacquer on top of another material.
+///
+/// Light is reflected off the coating by its Fresnel reflectance and otherwise scatters off the
+/// base, losing the part the coating reflects back on the way out. Light bouncing between the
+/// coating and the base is ignored, which darkens rough bases slightly. Back faces skip the
+/// coating.
+#[derive(Debug)]
+pub struct Layered {
+    base: Arc<dyn Material>,
+    refraction_index: f64,
+}
+
+impl Layered {
+    pub fn new(base: Arc<dyn Material>, refraction_index: f64) -> Self {
+        Self {
+            base,
+            refraction_index,
+        }
+    }
+
+    /// Fraction of the light from `direction` the coating reflects.
+    fn reflectance(&self, hit_record: &HitRecord, direction: DVec3) -> f64 {
+        let cos_theta = hit_record.normal.dot(direction.normalize());
+
+        fresnel_dielectric(cos_theta, self.refraction_index)
+    }
+}
+
+impl Material for Layered {
+    fn scatter(&self, ray: Ray, hit_record: HitRecord) -> Option<Scattered> {
+        if !hit_record.front_face {
+            return self.base.scatter(ray, hit_record);
+        }
+
+        let unit_direction = ray.direction.normalize();
+
+        if rand::thread_rng().gen_range(0.0..1.0) < self.reflectance(&hit_record, -unit_direction) {
+            let direction = reflect(unit_direction, hit_record.normal);
+
+            return Some(Scattered {
+                attenuation: DVec3::ONE,
+                direction: ray.spawn(hit_record.point, direction),
+                pdf: None,
+            });
+        }
+
+        // The chance of passing the coating on the way in cancels out with its transmittance.
+        let mut scattered = self.base.scatter(ray, hit_record.clone())?;
+        let direction = scattered.direction.direction;
+
+        if direction.dot(hit_record.normal) > 0.0 {
+            scattered.attenuation *= 1.0 - self.reflectance(&hit_record, direction);
+        }
+
+        scattered.pdf = scattered
+            .pdf
+            .map(|pdf| (1.0 - self.reflectance(&hit_record, -unit_direction)) * pdf);
+
+        Some(scattered)
+    }
+
+    fn emitted(&self, uv: DVec2, point: DVec3, wavelengths: SampledWavelengths) -> DVec3 {
+        self.base.emitted(uv, point, wavelengths)
+    }
+
+    fn scattering_pdf(&self, ray: Ray, hit_record: &HitRecord, direction: DVec3) -> f64 {
+        let pdf = self.base.scattering_pdf(ray, hit_record, direction);
+
+        if hit_record.front_face {
+            (1.0 - self.reflectance(hit_record, -ray.direction)) * pdf
+        } else {
+            pdf
+        }
+    }
+
+    fn evaluate(&self, ray: Ray, hit_record: &HitRecord, direction: DVec3) -> DVec3 {
+        let value = self.base.evaluate(ray, hit_record, direction);
+
+        if !hit_record.front_face {
+            return value;
+        }
+
+        let transmittance = (1.0 - self.reflectance(hit_record, -ray.direction))
+            * (1.0 - self.reflectance(hit_record, direction));
+
+        // Light from below the surface came through the base and not the coating.
+        if direction.dot(hit_record.normal) > 0.0 {
+            value * transmittance
+        } else {
+            value * (1.0 - self.reflectance(hit_record, -ray.direction))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::{lambertian::Lambertian, surface_hit};
+
+    #[test]
+    fn layered() {
+        let ray = Ray::new(DVec3::new(-1.0, 1.0, 0.0), DVec3::new(1.0, -1.0, 0.0));
+        let coated = |albedo: f64| {
+            let material = Arc::new(Layered::new(
+                Arc::new(Lambertian::with_solid(DVec3::splat(albedo))),
+                1.5,
+            ));
+            let hit_record = surface_hit(material.clone(), true);
+
+            (material, hit_record)
+        };
+
+        // Over black only the coating reflects, as often as Fresnel says.
+        let (material, hit_record) = coated(0.0);
+        let samples = 20000;
+        let reflected = (0..samples)
+            .map(|_| material.scatter(ray, hit_record.clone()).unwrap())
+            .filter(|scattered| scattered.pdf.is_none())
+            .count() as f64
+            / samples as f64;
+        let expected = fresnel_dielectric(0.5f64.sqrt(), 1.5);
+        assert!(
+            (reflected - expected).abs() < 0.01,
+            "{reflected} {expected}"
+        );
+
+        // The base is lit through the coating both ways, consistently with its density.
+        let (material, hit_record) = coated(1.0);
+        let mut throughput = 0.0;
+        for _ in 0..samples {
+            let scattered = material.scatter(ray, hit_record.clone()).unwrap();
+            let direction = scattered.direction.direction;
+
+            if let Some(pdf) = scattered.pdf {
+                let value = material.evaluate(ray, &hit_record, direction);
+                assert!((pdf - material.scattering_pdf(ray, &hit_record, direction)).abs() < 1e-9);
+                assert!(scattered.attenuation.abs_diff_eq(value / pdf, 1e-9));
+            }
+
+            throughput += scattered.attenuation.x;
+        }
+        let throughput = throughput / samples as f64;
+        assert!(throughput > 0.85 && throughput < 1.0, "{throughput}");
+    }
+}
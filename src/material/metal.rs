@@ -25,17 +25,17 @@ impl Metal {
 impl Material for Metal {
     fn scatter(&self, ray: Ray, hit_record: HitRecord) -> Option<Scattered> {
         let reflected = reflect(ray.direction.normalize(), hit_record.normal);
-        let direction = Ray::new_with_time(
+        let direction = ray.spawn(
             hit_record.point,
             reflected + self.fuzz * random_unit_vector(),
-            ray.time,
         );
-        let attenuation = self.albedo;
+        let attenuation = ray.wavelengths.upsample(self.albedo);
 
         if direction.direction.dot(hit_record.normal) > 0.0 {
             Some(Scattered {
                 attenuation,
                 direction,
+                pdf: None,
             })
         } else {
             None
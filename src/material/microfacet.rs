@@ -0,0 +1,212 @@
+use std::f64::consts::PI;
+
+use glam::{DVec2, DVec3};
+
+/// Orthonormal basis around a shading normal, microfacet math happens with the normal as `z`.
+#[derive(Debug, Clone, Copy)]
+pub struct Frame {
+    tangent: DVec3,
+    bitangent: DVec3,
+    normal: DVec3,
+}
+
+impl Frame {
+    pub fn new(normal: DVec3) -> Self {
+        let (tangent, bitangent) = normal.any_orthonormal_pair();
+
+        Self {
+            tangent,
+            bitangent,
+            normal,
+        }
+    }
+
+    pub fn to_local(&self, vector: DVec3) -> DVec3 {
+        DVec3::new(
+            vector.dot(self.tangent),
+            vector.dot(self.bitangent),
+            vector.dot(self.normal),
+        )
+    }
+
+    pub fn to_world(&self, vector: DVec3) -> DVec3 {
+        vector.x * self.tangent + vector.y * self.bitangent + vector.z * self.normal
+    }
+}
+
+/// The GGX or Trowbridge-Reitz distribution of microfacet normals with Smith masking.
+///
+/// Directions are in the local space of a [`Frame`] and point away from the surface.
+#[derive(Debug, Clone, Copy)]
+pub struct TrowbridgeReitz {
+    alpha_x: f64,
+    alpha_y: f64,
+}
+
+impl TrowbridgeReitz {
+    pub fn new(alpha_x: f64, alpha_y: f64) -> Self {
+        Self {
+            alpha_x: alpha_x.max(1e-4),
+            alpha_y: alpha_y.max(1e-4),
+        }
+    }
+
+    /// Maps the perceptual `roughness` from 0 to 1 to the width of the distribution.
+    pub fn from_roughness(roughness: f64) -> Self {
+        let alpha = roughness.clamp(0.0, 1.0).powi(2);
+
+        Self::new(alpha, alpha)
+    }
+
+    /// Below this the surface is treated as a perfect mirror.
+    pub fn is_smooth(&self) -> bool {
+        self.alpha_x.max(self.alpha_y) < 1e-3
+    }
+
+    /// Density of microfacet normals `normal` per projected area.
+    pub fn d(&self, normal: DVec3) -> f64 {
+        if normal.z <= 0.0 {
+            return 0.0;
+        }
+
+        let ellipse = (normal.x / self.alpha_x).powi(2)
+            + (normal.y / self.alpha_y).powi(2)
+            + normal.z * normal.z;
+
+        (PI * self.alpha_x * self.alpha_y * ellipse * ellipse).recip()
+    }
+
+    fn lambda(&self, direction: DVec3) -> f64 {
+        if direction.z == 0.0 {
+            return f64::INFINITY;
+        }
+
+        let alpha_squared_tan_squared = ((self.alpha_x * direction.x).powi(2)
+            + (self.alpha_y * direction.y).powi(2))
+            / (direction.z * direction.z);
+
+        0.5 * ((1.0 + alpha_squared_tan_squared).sqrt() - 1.0)
+    }
+
+    /// Fraction of the microfacets visible from `direction`.
+    pub fn g1(&self, direction: DVec3) -> f64 {
+        (1.0 + self.lambda(direction)).recip()
+    }
+
+    /// Fraction of the microfacets visible from both directions, height correlated.
+    pub fn g(&self, outgoing: DVec3, incoming: DVec3) -> f64 {
+        (1.0 + self.lambda(outgoing) + self.lambda(incoming)).recip()
+    }
+
+    /// Density of [`TrowbridgeReitz::sample_visible`] returning `normal` as seen from `direction`.
+    pub fn visible_pdf(&self, direction: DVec3, normal: DVec3) -> f64 {
+        if direction.z == 0.0 {
+            return 0.0;
+        }
+
+        self.g1(direction) / direction.z.abs() * self.d(normal) * direction.dot(normal).abs()
+    }
+
+    /// Samples a microfacet normal visible from `direction` following Heitz 2018.
+    pub fn sample_visible(&self, direction: DVec3, u: DVec2) -> DVec3 {
+        let flip = if direction.z < 0.0 { -1.0 } else { 1.0 };
+        let stretched = DVec3::new(
+            self.alpha_x * direction.x,
+            self.alpha_y * direction.y,
+            direction.z,
+        )
+        .normalize()
+            * flip;
+
+        let length_squared = stretched.x * stretched.x + stretched.y * stretched.y;
+        let tangent = if length_squared > 0.0 {
+            DVec3::new(-stretched.y, stretched.x, 0.0) / length_squared.sqrt()
+        } else {
+            DVec3::X
+        };
+        let bitangent = stretched.cross(tangent);
+
+        let radius = u.x.sqrt();
+        let phi = 2.0 * PI * u.y;
+        let p1 = radius * phi.cos();
+        let blend = 0.5 * (1.0 + stretched.z);
+        let p2 = (1.0 - blend) * (1.0 - p1 * p1).sqrt() + blend * radius * phi.sin();
+        let p3 = (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt();
+
+        let normal = p1 * tangent + p2 * bitangent + p3 * stretched;
+
+        DVec3::new(
+            self.alpha_x * normal.x,
+            self.alpha_y * normal.y,
+            normal.z.max(1e-6),
+        )
+        .normalize()
+    }
+}
+
+/// Midpoint rule over the directions with `cos theta` in `z_range` around `z`.
+#[cfg(test)]
+pub(crate) fn integrate_directions<T>(
+    z_range: std::ops::Range<f64>,
+    function: impl Fn(DVec3) -> T,
+) -> T
+where
+    T: std::ops::Mul<f64, Output = T> + std::iter::Sum,
+{
+    let (z_steps, phi_steps) = (4000, 256);
+    let z_step = (z_range.end - z_range.start) / z_steps as f64;
+    let cell = z_step * (2.0 * PI / phi_steps as f64);
+
+    (0..z_steps)
+        .flat_map(|i| (0..phi_steps).map(move |j| (i, j)))
+        .map(|(i, j)| {
+            let z = z_range.start + (i as f64 + 0.5) * z_step;
+            let phi = (j as f64 + 0.5) / phi_steps as f64 * 2.0 * PI;
+            let radius = (1.0 - z * z).sqrt();
+
+            function(DVec3::new(radius * phi.cos(), radius * phi.sin(), z)) * cell
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn trowbridge_reitz() {
+        let mut rng = rand::thread_rng();
+        let direction = DVec3::new(0.6, 0.0, 0.8);
+
+        for distribution in [
+            TrowbridgeReitz::from_roughness(0.3),
+            TrowbridgeReitz::from_roughness(0.8),
+            TrowbridgeReitz::new(0.2, 0.6),
+        ] {
+            // The projected microfacet area adds up to the macro surface.
+            let projected =
+                integrate_directions(0.0..1.0, |normal| distribution.d(normal) * normal.z);
+            assert!((projected - 1.0).abs() < 1e-2, "{projected}");
+
+            // Visible normals face the viewer and integrate to one.
+            let visible = integrate_directions(0.0..1.0, |normal| {
+                if normal.dot(direction) > 0.0 {
+                    distribution.visible_pdf(direction, normal)
+                } else {
+                    0.0
+                }
+            });
+            assert!((visible - 1.0).abs() < 1e-2, "{visible}");
+
+            for _ in 0..1000 {
+                let normal =
+                    distribution.sample_visible(direction, DVec2::new(rng.gen(), rng.gen()));
+
+                assert!((normal.length() - 1.0).abs() < 1e-9);
+                assert!(normal.z > 0.0 && normal.dot(direction) >= -1e-9);
+            }
+        }
+    }
+}
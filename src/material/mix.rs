@@ -0,0 +1,147 @@
+use std::sync::Arc;
+
+use glam::{DVec2, DVec3};
+use rand::Rng;
+
+use crate::{
+    hittable::HitRecord, prelude::Texture, ray::Ray, spectrum::SampledWavelengths, texture::Sample,
+};
+
+use super::{Material, Scattered};
+
+/// Blends two materials, every hit scatters off one of them picked by the weight.
+///
+/// The weight comes from the first channel of a texture and is the share of the second
+/// material, so a mask texture can paint one material onto the other.
+#[derive(Debug)]
+pub struct MixMaterial {
+    first: Arc<dyn Material>,
+    second: Arc<dyn Material>,
+    weight: Texture,
+}
+
+impl MixMaterial {
+    pub fn new(first: Arc<dyn Material>, second: Arc<dyn Material>, weight: Texture) -> Self {
+        Self {
+            first,
+            second,
+            weight,
+        }
+    }
+
+    pub fn with_weight(first: Arc<dyn Material>, second: Arc<dyn Material>, weight: f64) -> Self {
+        Self::new(first, second, Texture::Solid(DVec3::splat(weight)))
+    }
+
+    fn weight(&self, uv: DVec2, point: DVec3) -> f64 {
+        self.weight.sample(uv, point).x.clamp(0.0, 1.0)
+    }
+}
+
+impl Material for MixMaterial {
+    fn scatter(&self, ray: Ray, hit_record: HitRecord) -> Option<Scattered> {
+        let weight = self.weight(hit_record.uv, hit_record.point);
+        let material = if rand::thread_rng().gen_range(0.0..1.0) < weight {
+            &self.second
+        } else {
+            &self.first
+        };
+
+        let mut scattered = material.scatter(ray, hit_record.clone())?;
+
+        // The chance of the pick cancels out for specular scattering. Other directions could
+        // have come from either material, so they are weighted by both.
+        if scattered.pdf.is_some() {
+            let direction = scattered.direction.direction;
+            let pdf = self.scattering_pdf(ray, &hit_record, direction);
+
+            if pdf <= 0.0 {
+                return None;
+            }
+
+            scattered.attenuation = self.evaluate(ray, &hit_record, direction) / pdf;
+            scattered.pdf = Some(pdf);
+        }
+
+        Some(scattered)
+    }
+
+    fn emitted(&self, uv: DVec2, point: DVec3, wavelengths: SampledWavelengths) -> DVec3 {
+        self.first.emitted(uv, point, wavelengths).lerp(
+            self.second.emitted(uv, point, wavelengths),
+            self.weight(uv, point),
+        )
+    }
+
+    fn scattering_pdf(&self, ray: Ray, hit_record: &HitRecord, direction: DVec3) -> f64 {
+        let weight = self.weight(hit_record.uv, hit_record.point);
+
+        (1.0 - weight) * self.first.scattering_pdf(ray, hit_record, direction)
+            + weight * self.second.scattering_pdf(ray, hit_record, direction)
+    }
+
+    fn evaluate(&self, ray: Ray, hit_record: &HitRecord, direction: DVec3) -> DVec3 {
+        let weight = self.weight(hit_record.uv, hit_record.point);
+
+        self.first
+            .evaluate(ray, hit_record, direction)
+            .lerp(self.second.evaluate(ray, hit_record, direction), weight)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::{lambertian::Lambertian, metal::Metal, surface_hit};
+
+    #[test]
+    fn mix_material() {
+        let red = Arc::new(Lambertian::with_solid(DVec3::new(0.9, 0.1, 0.1)));
+        let blue = Arc::new(Lambertian::with_solid(DVec3::new(0.1, 0.1, 0.9)));
+        let mirror = Arc::new(Metal::new(DVec3::ONE, 0.0));
+
+        let ray = Ray::new(DVec3::new(-1.0, 1.0, 0.0), DVec3::new(1.0, -1.0, 0.0));
+        let sample = |material: Arc<MixMaterial>| {
+            let hit_record = surface_hit(material.clone(), true);
+            let samples = 20000;
+            let mut throughput = DVec3::ZERO;
+            let mut specular = 0;
+
+            for _ in 0..samples {
+                let scattered = material.scatter(ray, hit_record.clone()).unwrap();
+
+                if let Some(pdf) = scattered.pdf {
+                    let direction = scattered.direction.direction;
+                    let expected = material.scattering_pdf(ray, &hit_record, direction);
+                    assert!((pdf - expected).abs() < 1e-9);
+                } else {
+                    specular += 1;
+                }
+
+                throughput += scattered.attenuation;
+            }
+
+            (
+                throughput / samples as f64,
+                specular as f64 / samples as f64,
+            )
+        };
+
+        // Diffuse materials blend like their albedos.
+        let (throughput, _) = sample(Arc::new(MixMaterial::with_weight(red, blue, 0.25)));
+        assert!(
+            throughput.abs_diff_eq(DVec3::new(0.7, 0.1, 0.3), 1e-9),
+            "{throughput}"
+        );
+
+        // Specular picks keep their own throughput.
+        let diffuse = Arc::new(Lambertian::with_solid(DVec3::splat(0.5)));
+        let (throughput, specular) =
+            sample(Arc::new(MixMaterial::with_weight(diffuse, mirror, 0.3)));
+        assert!((specular - 0.3).abs() < 0.02, "{specular}");
+        assert!(
+            throughput.abs_diff_eq(DVec3::splat(0.65), 0.02),
+            "{throughput}"
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use std::f64::consts::PI;
+
+use glam::DVec3;
+
+use crate::{hittable::HitRecord, prelude::Texture, ray::Ray};
+
+use super::{util::random_unit_vector, Material, Scattered};
+
+/// Rough diffuse reflection after Oren and Nayar 1994, for clay, concrete or the moon.
+///
+/// The surface is made of V-shaped Lambertian facets whose slopes have a standard deviation of
+/// `sigma` degrees. Compared to [`Lambertian`](super::lambertian::Lambertian) it is flatter and
+/// reflects more light back towards where it came from. A `sigma` of zero is Lambertian.
+#[derive(Debug)]
+pub struct OrenNayar {
+    albedo: Texture,
+    a: f64,
+    b: f64,
+}
+
+impl OrenNayar {
+    pub fn new(albedo: Texture, sigma: f64) -> Self {
+        let sigma_squared = sigma.to_radians().powi(2);
+
+        Self {
+            albedo,
+            a: 1.0 - sigma_squared / (2.0 * (sigma_squared + 0.33)),
+            b: 0.45 * sigma_squared / (sigma_squared + 0.09),
+        }
+    }
+
+    pub fn with_solid(albedo: DVec3, sigma: f64) -> Self {
+        Self::new(Texture::Solid(albedo), sigma)
+    }
+
+    /// The reflectance relative to a Lambertian surface, with both directions pointing away.
+    fn roughness_factor(&self, normal: DVec3, outgoing: DVec3, incoming: DVec3) -> f64 {
+        let cos_outgoing = normal.dot(outgoing);
+        let cos_incoming = normal.dot(incoming);
+        let sin_outgoing = (1.0 - cos_outgoing * cos_outgoing).max(0.0).sqrt();
+        let sin_incoming = (1.0 - cos_incoming * cos_incoming).max(0.0).sqrt();
+
+        // Cosine of the azimuth between the directions, from their parts in the tangent plane.
+        let cos_phi = if sin_outgoing > 1e-4 && sin_incoming > 1e-4 {
+            let tangent_outgoing = outgoing - cos_outgoing * normal;
+            let tangent_incoming = incoming - cos_incoming * normal;
+
+            (tangent_outgoing.dot(tangent_incoming) / (sin_outgoing * sin_incoming)).max(0.0)
+        } else {
+            0.0
+        };
+
+        // Alpha is the larger of the two angles to the normal, beta the smaller one.
+        let (sin_alpha, tan_beta) = if cos_incoming.abs() > cos_outgoing.abs() {
+            (sin_outgoing, sin_incoming / cos_incoming.abs())
+        } else {
+            (sin_incoming, sin_outgoing / cos_outgoing.abs())
+        };
+
+        self.a + self.b * cos_phi * sin_alpha * tan_beta
+    }
+}
+
+impl Material for OrenNayar {
+    fn scatter(&self, ray: Ray, hit_record: HitRecord) -> Option<Scattered> {
+        let mut scatter_direction = hit_record.normal + random_unit_vector();
+
+        if scatter_direction.abs_diff_eq(DVec3::ZERO, 1e-8) {
+            scatter_direction = hit_record.normal;
+        }
+
+        let direction = ray.spawn(hit_record.point, scatter_direction);
+        let albedo = self
+            .albedo
+            .sample_spectrum(hit_record.uv, hit_record.point, ray.wavelengths);
+        let attenuation = albedo
+            * self.roughness_factor(
+                hit_record.normal,
+                -ray.direction.normalize(),
+                scatter_direction.normalize(),
+            );
+        let pdf = self.scattering_pdf(ray, &hit_record, scatter_direction);
+
+        Some(Scattered {
+            attenuation,
+            direction,
+            pdf: Some(pdf),
+        })
+    }
+
+    fn scattering_pdf(&self, _ray: Ray, hit_record: &HitRecord, direction: DVec3) -> f64 {
+        let cosine = hit_record.normal.dot(direction.normalize());
+
+        cosine.max(0.0) / PI
+    }
+
+    fn evaluate(&self, ray: Ray, hit_record: &HitRecord, direction: DVec3) -> DVec3 {
+        let direction = direction.normalize();
+
+        self.albedo
+            .sample_spectrum(hit_record.uv, hit_record.point, ray.wavelengths)
+            * self.roughness_factor(hit_record.normal, -ray.direction.normalize(), direction)
+            * self.scattering_pdf(ray, hit_record, direction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::material::surface_hit;
+
+    #[test]
+    fn oren_nayar() {
+        let albedo = DVec3::new(0.8, 0.6, 0.4);
+        let hit_record = |material: &Arc<OrenNayar>| surface_hit(material.clone(), true);
+        let ray = Ray::new(DVec3::new(-1.0, 0.5, 0.0), DVec3::new(1.0, -0.5, 0.0));
+
+        // Without roughness it is Lambertian.
+        let smooth = Arc::new(OrenNayar::with_solid(albedo, 0.0));
+        let scattered = smooth.scatter(ray, hit_record(&smooth)).unwrap();
+        assert!(scattered.attenuation.abs_diff_eq(albedo, 1e-12));
+
+        // Rough surfaces reflect more light back towards the light than away from it.
+        let rough = Arc::new(OrenNayar::with_solid(albedo, 30.0));
+        let backward = rough.evaluate(ray, &hit_record(&rough), -ray.direction);
+        let forward = rough.evaluate(ray, &hit_record(&rough), DVec3::new(1.0, 0.5, 0.0));
+        assert!(backward.x > 1.2 * forward.x, "{backward} {forward}");
+
+        // The sampled throughput agrees with the BSDF and stays below the albedo.
+        let samples = 20000;
+        let mut throughput = DVec3::ZERO;
+        for _ in 0..samples {
+            let scattered = rough.scatter(ray, hit_record(&rough)).unwrap();
+            let direction = scattered.direction.direction;
+            let pdf = scattered.pdf.unwrap();
+
+            let value = rough.evaluate(ray, &hit_record(&rough), direction);
+            assert!(scattered.attenuation.abs_diff_eq(value / pdf, 1e-9));
+            throughput += scattered.attenuation;
+        }
+        let throughput = throughput / samples as f64;
+        assert!(
+            throughput.x < albedo.x && throughput.x > 0.7 * albedo.x,
+            "{throughput}"
+        );
+    }
+}
@@ -0,0 +1,512 @@
+use std::f64::consts::PI;
+
+use glam::{DVec2, DVec3};
+use rand::Rng;
+
+use crate::{hittable::HitRecord, prelude::Texture, ray::Ray, texture::Sample};
+
+use super::{
+    microfacet::{Frame, TrowbridgeReitz},
+    rough_dielectric::RoughDielectric,
+    util::{random_unit_vector, reflect},
+    Material, Scattered,
+};
+
+/// Below this roughness the specular lobes would turn into mirrors, which can't be mixed with
+/// the other lobes without breaking light sampling.
+const MIN_ROUGHNESS: f64 = 0.05;
+
+/// Reflectance of the clearcoat at normal incidence, a refraction index of 1.5.
+const CLEARCOAT_REFLECTANCE: f64 = 0.04;
+
+/// The Disney principled BSDF after Burley 2012 and 2015, for base color / metallic / roughness
+/// workflows like the PBR materials of MTL and glTF.
+///
+/// The dielectric part is a diffuse lobe with retro-reflection and sheen under a specular lobe,
+/// blended by `transmission` into rough glass. `metallic` blends both into a specular lobe
+/// tinted by the base color, and a clearcoat layer goes on top of everything. Every parameter is
+/// either a constant or a texture, scalar ones read from the first channel of theirs.
+///
+/// Roughness is clamped to at least 0.05, so the material always has a density.
+#[derive(Debug)]
+pub struct Principled {
+    base_color: Texture,
+    metallic: Texture,
+    roughness: Texture,
+    specular: Texture,
+    sheen: Texture,
+    sheen_tint: Texture,
+    clearcoat: Texture,
+    clearcoat_roughness: Texture,
+    transmission: Texture,
+    refraction_index: f64,
+}
+
+fn solid(value: f64) -> Texture {
+    Texture::Solid(DVec3::splat(value))
+}
+
+/// The parameters sampled at one hit, and the chance of picking every lobe.
+///
+/// Colors are in the channels of the wavelengths of the ray, the chances come from RGB.
+struct Lobes {
+    frame: Frame,
+    outgoing: DVec3,
+    base_color: DVec3,
+    metallic: f64,
+    roughness: f64,
+    /// The sheen strength tinted towards the hue of the base color.
+    sheen_color: DVec3,
+    clearcoat: f64,
+    transmission: f64,
+    /// Specular reflectance at normal incidence, between the dielectric and the base color.
+    specular_color: DVec3,
+    specular: TrowbridgeReitz,
+    clearcoat_distribution: TrowbridgeReitz,
+    glass: RoughDielectric,
+    /// Sampling weights of the diffuse, specular, clearcoat and glass lobes, summing to one.
+    weights: [f64; 4],
+}
+
+impl Principled {
+    /// A rough dielectric with the default parameters of the Disney BRDF.
+    pub fn new(base_color: Texture) -> Self {
+        Self {
+            base_color,
+            metallic: solid(0.0),
+            roughness: solid(0.5),
+            specular: solid(0.5),
+            sheen: solid(0.0),
+            sheen_tint: solid(0.5),
+            clearcoat: solid(0.0),
+            clearcoat_roughness: solid(0.03),
+            transmission: solid(0.0),
+            refraction_index: 1.5,
+        }
+    }
+
+    pub fn with_solid(base_color: DVec3) -> Self {
+        Self::new(Texture::Solid(base_color))
+    }
+
+    pub fn metallic(self, metallic: f64) -> Self {
+        self.metallic_texture(solid(metallic))
+    }
+
+    pub fn metallic_texture(mut self, metallic: Texture) -> Self {
+        self.metallic = metallic;
+        self
+    }
+
+    /// Perceptual roughness from 0 to 1 of the specular and glass lobes.
+    pub fn roughness(self, roughness: f64) -> Self {
+        self.roughness_texture(solid(roughness))
+    }
+
+    pub fn roughness_texture(mut self, roughness: Texture) -> Self {
+        self.roughness = roughness;
+        self
+    }
+
+    /// Dielectric reflectance at normal incidence, where 1 stands for 8%. The default of 0.5
+    /// matches a refraction index of 1.5.
+    pub fn specular(self, specular: f64) -> Self {
+        self.specular_texture(solid(specular))
+    }
+
+    pub fn specular_texture(mut self, specular: Texture) -> Self {
+        self.specular = specular;
+        self
+    }
+
+    /// Strength of the grazing sheen of cloth.
+    pub fn sheen(self, sheen: f64) -> Self {
+        self.sheen_texture(solid(sheen))
+    }
+
+    pub fn sheen_texture(mut self, sheen: Texture) -> Self {
+        self.sheen = sheen;
+        self
+    }
+
+    /// Blends the sheen from white towards the hue of the base color.
+    pub fn sheen_tint(self, sheen_tint: f64) -> Self {
+        self.sheen_tint_texture(solid(sheen_tint))
+    }
+
+    pub fn sheen_tint_texture(mut self, sheen_tint: Texture) -> Self {
+        self.sheen_tint = sheen_tint;
+        self
+    }
+
+    pub fn clearcoat(self, clearcoat: f64) -> Self {
+        self.clearcoat_texture(solid(clearcoat))
+    }
+
+    pub fn clearcoat_texture(mut self, clearcoat: Texture) -> Self {
+        self.clearcoat = clearcoat;
+        self
+    }
+
+    pub fn clearcoat_roughness(self, clearcoat_roughness: f64) -> Self {
+        self.clearcoat_roughness_texture(solid(clearcoat_roughness))
+    }
+
+    pub fn clearcoat_roughness_texture(mut self, clearcoat_roughness: Texture) -> Self {
+        self.clearcoat_roughness = clearcoat_roughness;
+        self
+    }
+
+    /// Fraction of the dielectric part that is glass instead of diffuse.
+    pub fn transmission(self, transmission: f64) -> Self {
+        self.transmission_texture(solid(transmission))
+    }
+
+    pub fn transmission_texture(mut self, transmission: Texture) -> Self {
+        self.transmission = transmission;
+        self
+    }
+
+    /// Refraction index of the glass lobe.
+    pub fn refraction_index(mut self, refraction_index: f64) -> Self {
+        self.refraction_index = refraction_index;
+        self
+    }
+
+    fn lobes(&self, ray: Ray, hit_record: &HitRecord) -> Option<Lobes> {
+        let frame = Frame::new(hit_record.normal);
+        let outgoing = frame.to_local(-ray.direction.normalize());
+
+        if outgoing.z <= 0.0 {
+            return None;
+        }
+
+        let sample = |texture: &Texture| texture.sample(hit_record.uv, hit_record.point);
+        let scalar = |texture: &Texture| sample(texture).x.clamp(0.0, 1.0);
+
+        let base_color = sample(&self.base_color);
+        let metallic = scalar(&self.metallic);
+        let roughness = scalar(&self.roughness).max(MIN_ROUGHNESS);
+        let sheen = scalar(&self.sheen);
+        let clearcoat = scalar(&self.clearcoat);
+        let transmission = scalar(&self.transmission);
+
+        let dielectric_reflectance = DVec3::splat(0.08 * scalar(&self.specular));
+        let specular_color = dielectric_reflectance.lerp(base_color, metallic);
+
+        let diffuse_weight = (1.0 - metallic) * (1.0 - transmission);
+        let weights = [
+            diffuse_weight * (luminance(base_color) + sheen),
+            (1.0 - (1.0 - metallic) * transmission)
+                * luminance(schlick(specular_color, outgoing.z)),
+            clearcoat * schlick(DVec3::splat(CLEARCOAT_REFLECTANCE), outgoing.z).x,
+            (1.0 - metallic) * transmission,
+        ];
+        let total: f64 = weights.iter().sum();
+
+        if total <= 0.0 {
+            return None;
+        }
+
+        let tint = match luminance(base_color) {
+            luminance if luminance > 0.0 => base_color / luminance,
+            _ => DVec3::ONE,
+        };
+        let sheen_color = sheen * DVec3::ONE.lerp(tint, scalar(&self.sheen_tint));
+        let upsample = |rgb| ray.wavelengths.upsample(rgb);
+
+        Some(Lobes {
+            frame,
+            outgoing,
+            base_color: upsample(base_color),
+            metallic,
+            roughness,
+            sheen_color: upsample(sheen_color),
+            clearcoat,
+            transmission,
+            specular_color: upsample(specular_color),
+            specular: TrowbridgeReitz::from_roughness(roughness),
+            clearcoat_distribution: TrowbridgeReitz::from_roughness(
+                scalar(&self.clearcoat_roughness).max(MIN_ROUGHNESS),
+            ),
+            glass: RoughDielectric::with_roughness(self.refraction_index, roughness),
+            weights: weights.map(|weight| weight / total),
+        })
+    }
+}
+
+impl Lobes {
+    /// The reflection lobes, BSDF times cosine, for `incoming` above the surface.
+    fn reflection(&self, incoming: DVec3) -> DVec3 {
+        let outgoing = self.outgoing;
+        let half_vector = (outgoing + incoming).normalize();
+        let cos_d = incoming.dot(half_vector);
+
+        // Burley's diffuse, retro-reflective at grazing angles for rough surfaces.
+        let retro_reflection = 0.5 + 2.0 * self.roughness * cos_d * cos_d;
+        let diffuse = (1.0 + (retro_reflection - 1.0) * schlick_weight(incoming.z))
+            * (1.0 + (retro_reflection - 1.0) * schlick_weight(outgoing.z))
+            / PI;
+
+        let sheen = self.sheen_color * schlick_weight(cos_d);
+
+        let dielectric = (1.0 - self.metallic) * (1.0 - self.transmission);
+        let base = dielectric * (diffuse * self.base_color + sheen) * incoming.z
+            + (1.0 - (1.0 - self.metallic) * self.transmission)
+                * schlick(self.specular_color, outgoing.dot(half_vector))
+                * microfacet_reflection(&self.specular, outgoing, incoming);
+
+        let clearcoat = self.clearcoat
+            * schlick(
+                DVec3::splat(CLEARCOAT_REFLECTANCE),
+                outgoing.dot(half_vector),
+            )
+            .x
+            * microfacet_reflection(&self.clearcoat_distribution, outgoing, incoming);
+
+        base * self.clearcoat_attenuation() + DVec3::splat(clearcoat)
+    }
+
+    /// The light that makes it through the clearcoat to the layers below.
+    fn clearcoat_attenuation(&self) -> f64 {
+        1.0 - self.clearcoat * schlick(DVec3::splat(CLEARCOAT_REFLECTANCE), self.outgoing.z).x
+    }
+
+    fn reflection_pdf(&self, incoming: DVec3) -> f64 {
+        let outgoing = self.outgoing;
+        let half_vector = (outgoing + incoming).normalize();
+        let [diffuse, specular, clearcoat, _] = self.weights;
+
+        diffuse * incoming.z / PI
+            + specular * microfacet_pdf(&self.specular, outgoing, half_vector)
+            + clearcoat * microfacet_pdf(&self.clearcoat_distribution, outgoing, half_vector)
+    }
+}
+
+impl Material for Principled {
+    fn scatter(&self, ray: Ray, hit_record: HitRecord) -> Option<Scattered> {
+        let lobes = self.lobes(ray, &hit_record)?;
+        let mut rng = rand::thread_rng();
+
+        let mut choice = rng.gen_range(0.0..1.0);
+        let lobe = lobes
+            .weights
+            .iter()
+            .position(|weight| {
+                choice -= weight;
+                choice < 0.0
+            })
+            .unwrap_or(3);
+
+        let direction = match lobe {
+            0 => {
+                let direction = hit_record.normal + random_unit_vector();
+
+                if direction.abs_diff_eq(DVec3::ZERO, 1e-8) {
+                    hit_record.normal
+                } else {
+                    direction
+                }
+            }
+            1 | 2 => {
+                let distribution = if lobe == 1 {
+                    lobes.specular
+                } else {
+                    lobes.clearcoat_distribution
+                };
+                let normal =
+                    distribution.sample_visible(lobes.outgoing, DVec2::new(rng.gen(), rng.gen()));
+
+                lobes.frame.to_world(reflect(-lobes.outgoing, normal))
+            }
+            _ => {
+                lobes
+                    .glass
+                    .scatter(ray, hit_record.clone())?
+                    .direction
+                    .direction
+            }
+        };
+
+        // Weighing the sampled direction by all lobes keeps the variance of the mixture low.
+        let pdf = self.scattering_pdf(ray, &hit_record, direction);
+
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        Some(Scattered {
+            attenuation: self.evaluate(ray, &hit_record, direction) / pdf,
+            direction: ray.spawn(hit_record.point, direction),
+            pdf: Some(pdf),
+        })
+    }
+
+    fn scattering_pdf(&self, ray: Ray, hit_record: &HitRecord, direction: DVec3) -> f64 {
+        let Some(lobes) = self.lobes(ray, hit_record) else {
+            return 0.0;
+        };
+        let incoming = lobes.frame.to_local(direction.normalize());
+
+        let reflection = if incoming.z > 0.0 {
+            lobes.reflection_pdf(incoming)
+        } else {
+            0.0
+        };
+
+        reflection + lobes.weights[3] * lobes.glass.scattering_pdf(ray, hit_record, direction)
+    }
+
+    fn evaluate(&self, ray: Ray, hit_record: &HitRecord, direction: DVec3) -> DVec3 {
+        let Some(lobes) = self.lobes(ray, hit_record) else {
+            return DVec3::ZERO;
+        };
+        let incoming = lobes.frame.to_local(direction.normalize());
+
+        let glass = (1.0 - lobes.metallic)
+            * lobes.transmission
+            * lobes.clearcoat_attenuation()
+            * lobes.glass.evaluate(ray, hit_record, direction);
+
+        if incoming.z > 0.0 {
+            lobes.reflection(incoming) + glass
+        } else {
+            // Light refracted through the glass picks up the base color.
+            glass * lobes.base_color
+        }
+    }
+}
+
+/// GGX reflection times the cosine of `incoming`, without the Fresnel term.
+fn microfacet_reflection(distribution: &TrowbridgeReitz, outgoing: DVec3, incoming: DVec3) -> f64 {
+    let half_vector = (outgoing + incoming).normalize();
+
+    distribution.d(half_vector) * distribution.g(outgoing, incoming) / (4.0 * outgoing.z)
+}
+
+fn microfacet_pdf(distribution: &TrowbridgeReitz, outgoing: DVec3, half_vector: DVec3) -> f64 {
+    distribution.visible_pdf(outgoing, half_vector) / (4.0 * outgoing.dot(half_vector))
+}
+
+fn schlick_weight(cos_theta: f64) -> f64 {
+    (1.0 - cos_theta.clamp(0.0, 1.0)).powi(5)
+}
+
+fn schlick(reflectance: DVec3, cos_theta: f64) -> DVec3 {
+    reflectance.lerp(DVec3::ONE, schlick_weight(cos_theta))
+}
+
+fn luminance(color: DVec3) -> f64 {
+    color.dot(DVec3::new(0.2126, 0.7152, 0.0722))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use glam::DVec4;
+
+    use super::*;
+    use crate::material::{microfacet::integrate_directions, surface_hit};
+
+    #[test]
+    fn principled() {
+        // Between them every lobe is on and mixed with others.
+        let materials = [
+            Principled::with_solid(DVec3::new(0.8, 0.3, 0.2))
+                .sheen(1.0)
+                .clearcoat(1.0)
+                .clearcoat_roughness(0.2),
+            Principled::with_solid(DVec3::new(0.7, 0.9, 0.8))
+                .metallic(0.3)
+                .roughness(0.4)
+                .transmission(0.8),
+        ];
+
+        for material in materials {
+            let material = Arc::new(material);
+            let hit_record = surface_hit(material.clone(), true);
+            let ray = Ray::new(DVec3::new(-0.6, 1.0, 0.0), DVec3::new(0.6, -1.0, 0.0));
+            // The BSDF and the density of the mixture of the lobes over the sphere, which misses
+            // what the microfacets reflect below the horizon.
+            let integrated = [0.0..1.0, -1.0..0.0]
+                .map(|z_range| {
+                    integrate_directions(z_range, |direction| {
+                        let direction = DVec3::new(direction.x, direction.z, direction.y);
+
+                        material
+                            .evaluate(ray, &hit_record, direction)
+                            .extend(material.scattering_pdf(ray, &hit_record, direction))
+                    })
+                })
+                .iter()
+                .sum::<DVec4>();
+            let (integrated, total) = (integrated.truncate(), integrated.w);
+            assert!(total > 0.95 && total < 1.01, "{material:?} {total}");
+
+            // The sampled throughput matches the BSDF integrated over the sphere.
+            let samples = 100000;
+            let mut sampled = DVec3::ZERO;
+
+            for _ in 0..samples {
+                if let Some(scattered) = material.scatter(ray, hit_record.clone()) {
+                    sampled += scattered.attenuation;
+                }
+            }
+
+            let sampled = sampled / samples as f64;
+            assert!(
+                sampled.abs_diff_eq(integrated, 0.02),
+                "{material:?} {sampled} {integrated}"
+            );
+        }
+    }
+
+    #[test]
+    fn principled_lobes() {
+        let base_color = DVec3::new(0.9, 0.5, 0.2);
+        let ray = Ray::new(DVec3::new(-0.1, 1.0, 0.0), DVec3::new(0.1, -1.0, 0.0));
+        let mirrored = DVec3::new(0.1, 1.0, 0.0);
+        let highlight = |material: Principled| {
+            let material = Arc::new(material);
+            material.evaluate(ray, &surface_hit(material.clone(), true), mirrored)
+        };
+
+        // Metals tint their highlight by the base color.
+        let metal = highlight(
+            Principled::with_solid(base_color)
+                .metallic(1.0)
+                .roughness(0.3),
+        );
+        assert!(
+            (metal / metal.x).abs_diff_eq(base_color / base_color.x, 1e-6),
+            "{metal}"
+        );
+
+        // Dielectrics keep it white.
+        let plastic = highlight(Principled::with_solid(DVec3::ZERO).roughness(0.3));
+        assert!(plastic.x > 0.0, "{plastic}");
+        assert!(
+            plastic.abs_diff_eq(DVec3::splat(plastic.x), 1e-9),
+            "{plastic}"
+        );
+
+        // Sheen only shows between directions far apart, at grazing angles.
+        let sheen = |ray: Ray, direction: DVec3| {
+            let with_sheen = Arc::new(Principled::with_solid(base_color).sheen(1.0));
+            let without_sheen = Arc::new(Principled::with_solid(base_color));
+
+            with_sheen.evaluate(ray, &surface_hit(with_sheen.clone(), true), direction)
+                - without_sheen.evaluate(ray, &surface_hit(without_sheen.clone(), true), direction)
+        };
+
+        let head_on = sheen(Ray::new(DVec3::Y, DVec3::NEG_Y), DVec3::Y);
+        assert!(head_on.abs_diff_eq(DVec3::ZERO, 1e-9), "{head_on}");
+
+        let grazing = sheen(
+            Ray::new(DVec3::new(-1.0, 0.1, 0.0), DVec3::new(1.0, -0.1, 0.0)),
+            DVec3::new(1.0, 0.1, 0.0),
+        );
+        assert!(grazing.min_element() > 0.03, "{grazing}");
+    }
+}
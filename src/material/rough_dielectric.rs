@@ -0,0 +1,270 @@
+use glam::{DVec2, DVec3};
+use rand::Rng;
+
+use crate::{hittable::HitRecord, prelude::Texture, ray::Ray, texture::Sample};
+
+use super::{
+    microfacet::{Frame, TrowbridgeReitz},
+    util::{fresnel_dielectric, reflect, refract},
+    Material, Scattered,
+};
+
+/// Frosted glass: a dielectric with a GGX microfacet surface after Walter et al. 2007.
+///
+/// Reflection and transmission are chosen by the Fresnel reflectance of the sampled
+/// microfacet. Like [`Dielectric`](super::dielectric::Dielectric) it keeps the throughput of
+/// refracted rays instead of scaling radiance by the squared refraction index, which cancels
+/// out for closed objects anyway.
+#[derive(Debug)]
+pub struct RoughDielectric {
+    refraction_index: f64,
+    roughness: Texture,
+}
+
+/// The shading configuration of one hit, with the normal on the side of the incoming ray.
+struct Local {
+    frame: Frame,
+    outgoing: DVec3,
+    /// Refraction index of the far side over the near side.
+    eta: f64,
+    distribution: TrowbridgeReitz,
+}
+
+impl RoughDielectric {
+    pub fn new(refraction_index: f64, roughness: Texture) -> Self {
+        Self {
+            refraction_index,
+            roughness,
+        }
+    }
+
+    pub fn with_roughness(refraction_index: f64, roughness: f64) -> Self {
+        Self::new(refraction_index, Texture::Solid(DVec3::splat(roughness)))
+    }
+
+    fn local(&self, ray: Ray, hit_record: &HitRecord) -> Local {
+        let frame = Frame::new(hit_record.normal);
+
+        Local {
+            frame,
+            outgoing: frame.to_local(-ray.direction.normalize()),
+            eta: if hit_record.front_face {
+                self.refraction_index
+            } else {
+                self.refraction_index.recip()
+            },
+            distribution: TrowbridgeReitz::from_roughness(
+                self.roughness.sample(hit_record.uv, hit_record.point).x,
+            ),
+        }
+    }
+
+    /// The microfacet normal between `outgoing` and `incoming`, facing the outside, or `None`
+    /// for configurations no microfacet can produce.
+    fn half_vector(local: &Local, incoming: DVec3) -> Option<DVec3> {
+        let outgoing = local.outgoing;
+        let reflected = incoming.z > 0.0;
+
+        let half_vector = if reflected {
+            outgoing + incoming
+        } else {
+            outgoing + incoming * local.eta
+        };
+
+        if half_vector.length_squared() == 0.0 {
+            return None;
+        }
+
+        let normal = half_vector.normalize() * half_vector.z.signum();
+
+        // Both directions have to be on the side of the microfacet their macro surface is on.
+        if normal.dot(incoming) * incoming.z < 0.0 || normal.dot(outgoing) * outgoing.z < 0.0 {
+            return None;
+        }
+
+        Some(normal)
+    }
+
+    fn transmission_denominator(local: &Local, incoming: DVec3, normal: DVec3) -> f64 {
+        (incoming.dot(normal) + local.outgoing.dot(normal) / local.eta).powi(2)
+    }
+}
+
+impl Material for RoughDielectric {
+    fn scatter(&self, ray: Ray, hit_record: HitRecord) -> Option<Scattered> {
+        let local = self.local(ray, &hit_record);
+        let outgoing = local.outgoing;
+
+        if outgoing.z <= 0.0 {
+            return None;
+        }
+
+        let mut rng = rand::thread_rng();
+        let smooth = local.distribution.is_smooth();
+        let normal = if smooth {
+            DVec3::Z
+        } else {
+            local
+                .distribution
+                .sample_visible(outgoing, DVec2::new(rng.gen(), rng.gen()))
+        };
+
+        let cos_theta = outgoing.dot(normal);
+        let reflectance = fresnel_dielectric(cos_theta, local.eta);
+        let reflects = rng.gen_range(0.0..1.0) < reflectance;
+
+        let incoming = if reflects {
+            reflect(-outgoing, normal)
+        } else {
+            refract(-outgoing, normal, local.eta.recip())
+        };
+
+        if reflects != (incoming.z > 0.0) || incoming.z == 0.0 {
+            return None;
+        }
+
+        let direction = ray.spawn(hit_record.point, local.frame.to_world(incoming));
+
+        if smooth {
+            return Some(Scattered {
+                attenuation: DVec3::ONE,
+                direction,
+                pdf: None,
+            });
+        }
+
+        let visible_pdf = local.distribution.visible_pdf(outgoing, normal);
+        let pdf = if reflects {
+            reflectance * visible_pdf / (4.0 * cos_theta)
+        } else {
+            (1.0 - reflectance) * visible_pdf * incoming.dot(normal).abs()
+                / Self::transmission_denominator(&local, incoming, normal)
+        };
+
+        // The choice by Fresnel cancels with the BSDF, leaving the masking of the incoming side.
+        let attenuation = DVec3::splat(
+            local.distribution.g(outgoing, incoming) / local.distribution.g1(outgoing),
+        );
+
+        Some(Scattered {
+            attenuation,
+            direction,
+            pdf: Some(pdf),
+        })
+    }
+
+    fn scattering_pdf(&self, ray: Ray, hit_record: &HitRecord, direction: DVec3) -> f64 {
+        let local = self.local(ray, hit_record);
+        let incoming = local.frame.to_local(direction.normalize());
+
+        if local.outgoing.z <= 0.0 || local.distribution.is_smooth() {
+            return 0.0;
+        }
+
+        let Some(normal) = Self::half_vector(&local, incoming) else {
+            return 0.0;
+        };
+
+        let cos_theta = local.outgoing.dot(normal);
+        let reflectance = fresnel_dielectric(cos_theta, local.eta);
+        let visible_pdf = local.distribution.visible_pdf(local.outgoing, normal);
+
+        if incoming.z > 0.0 {
+            reflectance * visible_pdf / (4.0 * cos_theta)
+        } else {
+            (1.0 - reflectance) * visible_pdf * incoming.dot(normal).abs()
+                / Self::transmission_denominator(&local, incoming, normal)
+        }
+    }
+
+    fn evaluate(&self, ray: Ray, hit_record: &HitRecord, direction: DVec3) -> DVec3 {
+        let local = self.local(ray, hit_record);
+        let outgoing = local.outgoing;
+        let incoming = local.frame.to_local(direction.normalize());
+
+        if outgoing.z <= 0.0 || local.distribution.is_smooth() {
+            return DVec3::ZERO;
+        }
+
+        let Some(normal) = Self::half_vector(&local, incoming) else {
+            return DVec3::ZERO;
+        };
+
+        let distribution = local.distribution;
+        let cos_theta = outgoing.dot(normal);
+        let reflectance = fresnel_dielectric(cos_theta, local.eta);
+        let d = distribution.d(normal);
+        let g = distribution.g(outgoing, incoming);
+
+        let value = if incoming.z > 0.0 {
+            reflectance * d * g / (4.0 * outgoing.z)
+        } else {
+            (1.0 - reflectance) * d * g * incoming.dot(normal).abs() * cos_theta
+                / (outgoing.z * Self::transmission_denominator(&local, incoming, normal))
+        };
+
+        DVec3::splat(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::material::{microfacet::integrate_directions, surface_hit};
+
+    #[test]
+    fn rough_dielectric() {
+        let material = Arc::new(RoughDielectric::with_roughness(1.5, 0.4));
+
+        for front_face in [true, false] {
+            let hit_record = surface_hit(material.clone(), front_face);
+            let ray = Ray::new(DVec3::new(-0.5, 1.0, 0.0), DVec3::new(0.5, -1.0, 0.0));
+
+            // The sampled throughput matches the BSDF integrated over the sphere, for both
+            // reflection and transmission.
+            let samples = 200000;
+            let mut sampled = [0.0; 2];
+
+            for _ in 0..samples {
+                if let Some(scattered) = material.scatter(ray, hit_record.clone()) {
+                    let direction = scattered.direction.direction;
+                    let pdf = material.scattering_pdf(ray, &hit_record, direction);
+
+                    assert!(
+                        (scattered.pdf.unwrap() - pdf).abs() < 1e-6 * pdf,
+                        "{front_face} {direction} {pdf}"
+                    );
+                    sampled[(direction.y < 0.0) as usize] += scattered.attenuation.x;
+                }
+            }
+
+            let integrated = [0.0..1.0, -1.0..0.0].map(|z_range| {
+                integrate_directions(z_range, |direction| {
+                    let direction = DVec3::new(direction.x, direction.z, direction.y);
+
+                    material.evaluate(ray, &hit_record, direction).x
+                })
+            });
+
+            for (sampled, integrated) in sampled.iter().zip(integrated) {
+                let sampled = sampled / samples as f64;
+
+                assert!(
+                    (sampled - integrated).abs() < 0.01,
+                    "{front_face} {sampled} {integrated}"
+                );
+            }
+        }
+
+        // Total internal reflection at a grazing angle from the inside.
+        let hit_record = surface_hit(material.clone(), false);
+        let smooth = RoughDielectric::with_roughness(1.5, 0.0);
+        let ray = Ray::new(DVec3::new(-1.0, 0.2, 0.0), DVec3::new(1.0, -0.2, 0.0));
+        for _ in 0..100 {
+            let scattered = smooth.scatter(ray, hit_record.clone()).unwrap();
+            assert!(scattered.direction.direction.y > 0.0);
+        }
+    }
+}
@@ -56,3 +56,57 @@ pub fn refract(uv: DVec3, n: DVec3, etai_over_etat: f64) -> DVec3 {
 
     r_out_perp + r_out_parallel
 }
+
+/// Unpolarized Fresnel reflectance of a dielectric interface, `eta` is the refraction index of
+/// the far side over the one of the side `cos_theta_i` is measured on.
+pub fn fresnel_dielectric(cos_theta_i: f64, eta: f64) -> f64 {
+    let cos_theta_i = cos_theta_i.clamp(0.0, 1.0);
+    let sin2_theta_t = (1.0 - cos_theta_i * cos_theta_i) / (eta * eta);
+
+    if sin2_theta_t >= 1.0 {
+        return 1.0;
+    }
+
+    let cos_theta_t = (1.0 - sin2_theta_t).sqrt();
+    let parallel = (eta * cos_theta_i - cos_theta_t) / (eta * cos_theta_i + cos_theta_t);
+    let perpendicular = (cos_theta_i - eta * cos_theta_t) / (cos_theta_i + eta * cos_theta_t);
+
+    0.5 * (parallel * parallel + perpendicular * perpendicular)
+}
+
+/// Schlick's approximation of [`fresnel_dielectric`], still reflecting everything past the
+/// critical angle.
+pub fn fresnel_schlick(cos_theta_i: f64, eta: f64) -> f64 {
+    let cos_theta_i = cos_theta_i.clamp(0.0, 1.0);
+
+    if (1.0 - cos_theta_i * cos_theta_i) / (eta * eta) >= 1.0 {
+        return 1.0;
+    }
+
+    let r0 = ((1.0 - eta) / (1.0 + eta)).powi(2);
+
+    r0 + (1.0 - r0) * (1.0 - cos_theta_i).powi(5)
+}
+
+/// Fresnel reflectance of a conductor with the complex refraction index `eta + i k` per channel.
+pub fn fresnel_conductor(cos_theta_i: f64, eta: DVec3, k: DVec3) -> DVec3 {
+    let cos_theta_i = cos_theta_i.clamp(0.0, 1.0);
+    let cos2 = cos_theta_i * cos_theta_i;
+    let sin2 = 1.0 - cos2;
+
+    let eta2 = eta * eta;
+    let k2 = k * k;
+    let t0 = eta2 - k2 - DVec3::splat(sin2);
+    let a2_plus_b2 = (t0 * t0 + 4.0 * eta2 * k2).powf(0.5);
+    let a = (0.5 * (a2_plus_b2 + t0)).max(DVec3::ZERO).powf(0.5);
+
+    let t1 = a2_plus_b2 + DVec3::splat(cos2);
+    let t2 = 2.0 * cos_theta_i * a;
+    let perpendicular = (t1 - t2) / (t1 + t2);
+
+    let t3 = cos2 * a2_plus_b2 + DVec3::splat(sin2 * sin2);
+    let t4 = t2 * sin2;
+    let parallel = perpendicular * (t3 - t4) / (t3 + t4);
+
+    0.5 * (parallel + perpendicular)
+}
@@ -0,0 +1,300 @@
+//! Tracking shared by [`ConstantMedium`](crate::constant_medium::ConstantMedium) and
+//! [`HeterogeneousMedium`](crate::heterogeneous_medium::HeterogeneousMedium).
+//!
+//! # Phase functions
+//!
+//! Where a ray collides with a medium its phase function, a [`Material`], scatters it into a
+//! new direction and reports its density for light sampling. Media start out
+//! [`Isotropic`](crate::material::isotropic::Isotropic), while
+//! [`HenyeyGreenstein`](crate::material::henyey_greenstein::HenyeyGreenstein) scatters light
+//! forwards or backwards. The albedo of the phase function tints every scattering event.
+
+use std::{ops::Range, sync::Arc};
+
+use glam::{DVec2, DVec3};
+use rand::Rng;
+
+use crate::{
+    hittable::{HitRecord, Hittable},
+    material::{Material, Scattered},
+    ray::Ray,
+    spectrum::SampledWavelengths,
+};
+
+/// Per channel cross sections of a medium, scaled by its density at every point.
+///
+/// A ray is absorbed with `sigma_a` and scattered with `sigma_s`, so channels with a higher
+/// `sigma_t` fade out sooner and the color of the medium comes from the ratio between them.
+/// Spectral rays upsample both and track every wavelength on its own.
+/// Media given coefficients use a white phase function, the others scatter in every channel
+/// alike and take their color from the albedo of the phase function.
+#[derive(Debug, Clone, Copy)]
+pub struct Coefficients {
+    pub sigma_a: DVec3,
+    pub sigma_s: DVec3,
+}
+
+impl Coefficients {
+    /// Scattering only, leaving the color to the phase function.
+    pub const GRAY: Self = Self {
+        sigma_a: DVec3::ZERO,
+        sigma_s: DVec3::ONE,
+    };
+
+    pub fn sigma_t(&self) -> DVec3 {
+        self.sigma_a + self.sigma_s
+    }
+
+    /// The coefficients in the channels of `wavelengths`.
+    pub fn at(&self, wavelengths: SampledWavelengths) -> Self {
+        Self {
+            sigma_a: wavelengths.upsample(self.sigma_a),
+            sigma_s: wavelengths.upsample(self.sigma_s),
+        }
+    }
+}
+
+/// The `t` range along `ray` that lies inside `boundary`, even if the ray starts inside of it.
+pub(crate) fn boundary_span(
+    boundary: &dyn Hittable,
+    ray: Ray,
+    t_range: Range<f64>,
+) -> Option<Range<f64>> {
+    let entry = boundary.hit(ray, f64::NEG_INFINITY..f64::INFINITY)?;
+    let exit = boundary.hit(ray, (entry.t + 0.0001)..f64::INFINITY)?;
+
+    let start = entry.t.max(t_range.start).max(0.0);
+    let end = exit.t.min(t_range.end);
+
+    if start < end {
+        Some(start..end)
+    } else {
+        None
+    }
+}
+
+/// Spectral delta tracking through `span` against the majorant `max_density * max(sigma_t)`.
+///
+/// Every tentative collision is either a real one, scattering with the phase function, or a
+/// null one the ray passes through. Both pick their event by the average over the channels and
+/// carry the per channel difference as a weight. Gray media always end up with a weight of one,
+/// so null collisions are skipped internally and only real scattering is reported. Colored null
+/// collisions are reported as hits that let the ray continue unchanged with their weight.
+pub(crate) fn track(
+    ray: Ray,
+    span: Range<f64>,
+    coefficients: Coefficients,
+    max_density: f64,
+    density: impl Fn(DVec3) -> f64,
+    phase_function: &Arc<dyn Material>,
+) -> Option<HitRecord> {
+    let coefficients = coefficients.at(ray.wavelengths);
+    let sigma_t = coefficients.sigma_t();
+    let majorant = max_density * sigma_t.max_element();
+
+    if majorant <= 0.0 {
+        return None;
+    }
+
+    let mut rng = rand::thread_rng();
+    let step = (majorant * ray.direction.length()).recip();
+    let mut t = span.start;
+
+    loop {
+        t -= step * (1.0 - rng.gen_range(0.0f64..1.0)).ln();
+
+        if t >= span.end {
+            return None;
+        }
+
+        let point = ray.at(t);
+        let local_density = density(point);
+        let real_probability = (local_density * sigma_t / majorant)
+            .dot(DVec3::ONE)
+            .clamp(0.0, 3.0)
+            / 3.0;
+
+        let material = if rng.gen_range(0.0..1.0) < real_probability {
+            let weight = local_density * coefficients.sigma_s / (majorant * real_probability);
+
+            weighted(phase_function, weight)
+        } else {
+            let sigma_n = majorant - local_density * sigma_t;
+            let weight = sigma_n / (majorant * (1.0 - real_probability));
+
+            if weight.abs_diff_eq(DVec3::ONE, 1e-9) {
+                continue;
+            }
+
+            Arc::new(PassThrough { weight })
+        };
+
+        return Some(HitRecord {
+            point,
+            normal: DVec3::X,
+            t,
+            uv: DVec2::ZERO,
+            material,
+            front_face: true,
+        });
+    }
+}
+
+/// Estimates the per channel fraction of light that passes through `span` with ratio tracking.
+#[cfg(test)]
+pub(crate) fn transmittance(
+    ray: Ray,
+    span: Range<f64>,
+    coefficients: Coefficients,
+    max_density: f64,
+    density: impl Fn(DVec3) -> f64,
+) -> DVec3 {
+    let sigma_t = coefficients.at(ray.wavelengths).sigma_t();
+    let majorant = max_density * sigma_t.max_element();
+
+    if majorant <= 0.0 {
+        return DVec3::ONE;
+    }
+
+    let mut rng = rand::thread_rng();
+    let step = (majorant * ray.direction.length()).recip();
+    let mut transmittance = DVec3::ONE;
+    let mut t = span.start;
+
+    loop {
+        t -= step * (1.0 - rng.gen_range(0.0f64..1.0)).ln();
+
+        if t >= span.end {
+            return transmittance;
+        }
+
+        transmittance *= 1.0 - density(ray.at(t)) * sigma_t / majorant;
+    }
+}
+
+fn weighted(phase_function: &Arc<dyn Material>, weight: DVec3) -> Arc<dyn Material> {
+    if weight.abs_diff_eq(DVec3::ONE, 1e-9) {
+        phase_function.clone()
+    } else {
+        Arc::new(Weighted {
+            phase_function: phase_function.clone(),
+            weight,
+        })
+    }
+}
+
+/// A phase function scaled by the weight of the collision that chose it.
+#[derive(Debug)]
+struct Weighted {
+    phase_function: Arc<dyn Material>,
+    weight: DVec3,
+}
+
+impl Material for Weighted {
+    fn scatter(&self, ray: Ray, hit_record: HitRecord) -> Option<Scattered> {
+        let mut scattered = self.phase_function.scatter(ray, hit_record)?;
+        scattered.attenuation *= self.weight;
+
+        Some(scattered)
+    }
+
+    fn scattering_pdf(&self, ray: Ray, hit_record: &HitRecord, direction: DVec3) -> f64 {
+        self.phase_function
+            .scattering_pdf(ray, hit_record, direction)
+    }
+
+    fn evaluate(&self, ray: Ray, hit_record: &HitRecord, direction: DVec3) -> DVec3 {
+        self.weight * self.phase_function.evaluate(ray, hit_record, direction)
+    }
+}
+
+/// A null collision, the ray continues in the same direction.
+///
+/// It counts as a specular bounce, so light sampling from the previous vertex stays unbiased:
+/// shadow rays treat it as an occluder while paths through it hit lights at full weight.
+#[derive(Debug)]
+struct PassThrough {
+    weight: DVec3,
+}
+
+impl Material for PassThrough {
+    fn scatter(&self, ray: Ray, hit_record: HitRecord) -> Option<Scattered> {
+        Some(Scattered {
+            attenuation: self.weight,
+            direction: ray.spawn(hit_record.point, ray.direction),
+            pdf: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::isotropic::Isotropic;
+
+    #[test]
+    fn chromatic_tracking() {
+        let coefficients = Coefficients {
+            sigma_a: DVec3::new(0.1, 0.8, 1.5),
+            sigma_s: DVec3::new(0.4, 0.2, 0.1),
+        };
+        let phase_function: Arc<dyn Material> = Arc::new(Isotropic::with_solid(DVec3::ONE));
+        let expected = (-coefficients.sigma_t()).exp();
+
+        // Follows null collisions until the ray scatters or leaves the unit length slab.
+        let samples = 40000;
+        let mut escaped = DVec3::ZERO;
+        for _ in 0..samples {
+            let mut ray = Ray::new(DVec3::ZERO, DVec3::X);
+            let mut weight = DVec3::ONE;
+
+            while let Some(hit) = track(
+                ray,
+                0.0..1.0 - ray.origin.x,
+                coefficients,
+                1.0,
+                |_| 1.0,
+                &phase_function,
+            ) {
+                let scattered = hit.material.scatter(ray, hit.clone()).unwrap();
+
+                if scattered.pdf.is_some() {
+                    weight = DVec3::ZERO;
+                    break;
+                }
+
+                weight *= scattered.attenuation;
+                ray = Ray::new(hit.point, ray.direction);
+            }
+
+            escaped += weight;
+        }
+        let escaped = escaped / samples as f64;
+
+        let ray = Ray::new(DVec3::ZERO, DVec3::X);
+        let estimated = (0..samples)
+            .map(|_| transmittance(ray, 0.0..1.0, coefficients, 1.0, |_| 1.0))
+            .sum::<DVec3>()
+            / samples as f64;
+
+        assert!(escaped.abs_diff_eq(expected, 0.02), "{escaped} {expected}");
+        assert!(
+            estimated.abs_diff_eq(expected, 0.02),
+            "{estimated} {expected}"
+        );
+
+        // Spectral rays are attenuated at their own wavelengths.
+        let wavelengths = SampledWavelengths::stratified(0.2);
+        let ray = Ray { wavelengths, ..ray };
+        let expected = (-coefficients.at(wavelengths).sigma_t()).exp();
+        let estimated = (0..samples)
+            .map(|_| transmittance(ray, 0.0..1.0, coefficients, 1.0, |_| 1.0))
+            .sum::<DVec3>()
+            / samples as f64;
+
+        assert!(
+            estimated.abs_diff_eq(expected, 0.02),
+            "{estimated} {expected}"
+        );
+    }
+}
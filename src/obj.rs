@@ -0,0 +1,689 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Display},
+    fs, io,
+    path::{Path, PathBuf},
+    str::{FromStr, SplitWhitespace},
+    sync::Arc,
+};
+
+use glam::{DVec2, DVec3};
+use image::ImageError;
+
+use crate::{
+    material::{
+        dielectric::Dielectric, diffuse_light::DiffuseLight, lambertian::Lambertian, metal::Metal,
+        principled::Principled, Material,
+    },
+    texture::{Texture, TextureImage},
+    triangle::TriangleMesh,
+};
+
+#[derive(Debug)]
+pub enum ObjError {
+    Io(PathBuf, io::Error),
+    Image(PathBuf, ImageError),
+    Parse {
+        file: PathBuf,
+        line: usize,
+        message: String,
+    },
+}
+
+impl Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjError::Io(path, error) => write!(f, "{}: {}", path.display(), error),
+            ObjError::Image(path, error) => write!(f, "{}: {}", path.display(), error),
+            ObjError::Parse {
+                file,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", file.display(), line, message),
+        }
+    }
+}
+
+impl Error for ObjError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ObjError::Io(_, error) => Some(error),
+            ObjError::Image(_, error) => Some(error),
+            ObjError::Parse { .. } => None,
+        }
+    }
+}
+
+/// Loads a Wavefront OBJ file together with the MTL libraries it references.
+///
+/// Every group (`g`/`o`) becomes one mesh per material used inside of it. Materials with the
+/// PBR extension keys (`Pr`, `Pm`, `Ps`, `Pc`, `Pcr`, `map_Pr` and `map_Pm`) become
+/// [`Principled`] materials.
+pub fn load_obj(path: &Path) -> Result<Vec<TriangleMesh>, ObjError> {
+    let source = read_file(path)?;
+    let obj = Obj::parse(&source, path)?;
+    let base_dir = path.parent().unwrap_or(Path::new(""));
+
+    let mut materials = HashMap::new();
+
+    for library in &obj.material_libraries {
+        let library_path = base_dir.join(library);
+        let source = read_file(&library_path)?;
+
+        for mtl in Mtl::parse(&source, &library_path)? {
+            let material = mtl.to_material(library_path.parent().unwrap_or(base_dir))?;
+            materials.insert(mtl.name, material);
+        }
+    }
+
+    let default_material: Arc<dyn Material> = Arc::new(Lambertian::with_solid(DVec3::splat(0.8)));
+
+    Ok(obj
+        .meshes
+        .into_iter()
+        .filter(|mesh| !mesh.indices.is_empty())
+        .map(|mesh| {
+            let material = mesh
+                .material
+                .as_ref()
+                .and_then(|name| materials.get(name))
+                .unwrap_or(&default_material)
+                .clone();
+
+            TriangleMesh::new(
+                mesh.positions,
+                mesh.normals,
+                mesh.uvs,
+                mesh.indices,
+                material,
+            )
+        })
+        .collect())
+}
+
+fn read_file(path: &Path) -> Result<String, ObjError> {
+    fs::read_to_string(path).map_err(|error| ObjError::Io(path.to_path_buf(), error))
+}
+
+#[derive(Debug, Default)]
+struct Obj {
+    material_libraries: Vec<String>,
+    meshes: Vec<ObjMesh>,
+}
+
+#[derive(Debug, Default)]
+struct ObjMesh {
+    material: Option<String>,
+    positions: Vec<DVec3>,
+    normals: Vec<DVec3>,
+    uvs: Vec<DVec2>,
+    indices: Vec<[usize; 3]>,
+}
+
+type FaceVertex = (usize, Option<usize>, Option<usize>);
+
+impl Obj {
+    fn parse(source: &str, file: &Path) -> Result<Self, ObjError> {
+        let mut obj = Obj::default();
+
+        let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        let mut uvs = Vec::new();
+
+        let mut material = None;
+        let mut faces: Vec<Vec<FaceVertex>> = Vec::new();
+
+        for (index, line) in source.lines().enumerate() {
+            let mut parser = LineParser::new(line, file, index + 1);
+
+            let Some(keyword) = parser.keyword() else {
+                continue;
+            };
+
+            match keyword {
+                "v" => positions.push(parser.dvec3()?),
+                "vn" => normals.push(parser.dvec3()?),
+                "vt" => uvs.push(DVec2::new(parser.number()?, parser.optional_number()?)),
+                "f" => {
+                    let mut face = Vec::new();
+
+                    for vertex in parser.remaining() {
+                        face.push(parser.face_vertex(
+                            vertex,
+                            positions.len(),
+                            uvs.len(),
+                            normals.len(),
+                        )?);
+                    }
+
+                    if face.len() < 3 {
+                        return Err(parser.error("face needs at least three vertices"));
+                    }
+
+                    faces.push(face);
+                }
+                "g" | "o" => {
+                    obj.push_mesh(&faces, material.clone(), &positions, &uvs, &normals);
+                    faces.clear();
+                }
+                "usemtl" => {
+                    obj.push_mesh(&faces, material.clone(), &positions, &uvs, &normals);
+                    faces.clear();
+                    material = Some(parser.name()?);
+                }
+                "mtllib" => obj.material_libraries.extend(parser.names()?),
+                _ => {}
+            }
+        }
+
+        obj.push_mesh(&faces, material, &positions, &uvs, &normals);
+
+        Ok(obj)
+    }
+
+    fn push_mesh(
+        &mut self,
+        faces: &[Vec<FaceVertex>],
+        material: Option<String>,
+        positions: &[DVec3],
+        uvs: &[DVec2],
+        normals: &[DVec3],
+    ) {
+        if faces.is_empty() {
+            return;
+        }
+
+        let has_uvs = faces.iter().flatten().all(|vertex| vertex.1.is_some());
+        let has_normals = faces.iter().flatten().all(|vertex| vertex.2.is_some());
+
+        let mut mesh = ObjMesh {
+            material,
+            ..Default::default()
+        };
+        let mut vertex_indices = HashMap::new();
+
+        for face in faces {
+            let face = face
+                .iter()
+                .map(|&(position, uv, normal)| {
+                    let uv = uv.filter(|_| has_uvs);
+                    let normal = normal.filter(|_| has_normals);
+
+                    *vertex_indices
+                        .entry((position, uv, normal))
+                        .or_insert_with(|| {
+                            mesh.positions.push(positions[position]);
+                            mesh.uvs.extend(uv.map(|uv| uvs[uv]));
+                            mesh.normals.extend(normal.map(|normal| normals[normal]));
+                            mesh.positions.len() - 1
+                        })
+                })
+                .collect::<Vec<usize>>();
+
+            for i in 1..face.len() - 1 {
+                mesh.indices.push([face[0], face[i], face[i + 1]]);
+            }
+        }
+
+        self.meshes.push(mesh);
+    }
+}
+
+#[derive(Debug, Default)]
+struct Mtl {
+    name: String,
+    diffuse: Option<DVec3>,
+    diffuse_map: Option<String>,
+    specular: Option<DVec3>,
+    specular_exponent: Option<f64>,
+    emission: Option<DVec3>,
+    refraction_index: Option<f64>,
+    dissolve: Option<f64>,
+    illumination: Option<u32>,
+    roughness: Option<f64>,
+    roughness_map: Option<String>,
+    metallic: Option<f64>,
+    metallic_map: Option<String>,
+    sheen: Option<f64>,
+    clearcoat: Option<f64>,
+    clearcoat_roughness: Option<f64>,
+}
+
+impl Mtl {
+    fn parse(source: &str, file: &Path) -> Result<Vec<Self>, ObjError> {
+        let mut materials: Vec<Mtl> = Vec::new();
+
+        for (index, line) in source.lines().enumerate() {
+            let mut parser = LineParser::new(line, file, index + 1);
+
+            let Some(keyword) = parser.keyword() else {
+                continue;
+            };
+
+            if keyword == "newmtl" {
+                materials.push(Mtl {
+                    name: parser.name()?,
+                    ..Default::default()
+                });
+                continue;
+            }
+
+            let Some(material) = materials.last_mut() else {
+                return Err(parser.error(&format!("'{keyword}' before any 'newmtl'")));
+            };
+
+            match keyword {
+                "Kd" => material.diffuse = Some(parser.dvec3()?),
+                "Ks" => material.specular = Some(parser.dvec3()?),
+                "Ns" => material.specular_exponent = Some(parser.number()?),
+                "Ke" => material.emission = Some(parser.dvec3()?),
+                "Ni" => material.refraction_index = Some(parser.number()?),
+                "d" => material.dissolve = Some(parser.number()?),
+                "Tr" => material.dissolve = Some(1.0 - parser.number::<f64>()?),
+                "illum" => material.illumination = Some(parser.number()?),
+                "map_Kd" => material.diffuse_map = Some(parser.map_name()?),
+                "Pr" => material.roughness = Some(parser.number()?),
+                "map_Pr" => material.roughness_map = Some(parser.map_name()?),
+                "Pm" => material.metallic = Some(parser.number()?),
+                "map_Pm" => material.metallic_map = Some(parser.map_name()?),
+                "Ps" => material.sheen = Some(parser.number()?),
+                "Pc" => material.clearcoat = Some(parser.number()?),
+                "Pcr" => material.clearcoat_roughness = Some(parser.number()?),
+                _ => {}
+            }
+        }
+
+        Ok(materials)
+    }
+
+    fn to_material(&self, base_dir: &Path) -> Result<Arc<dyn Material>, ObjError> {
+        let diffuse = self.diffuse.unwrap_or(DVec3::splat(0.8));
+        let specular = self.specular.unwrap_or(DVec3::ZERO);
+
+        let transparent = self.dissolve.is_some_and(|dissolve| dissolve < 1.0)
+            || matches!(self.illumination, Some(4 | 6 | 7 | 9));
+        let metallic = matches!(self.illumination, Some(3 | 5))
+            || (diffuse == DVec3::ZERO && specular != DVec3::ZERO);
+
+        if let Some(emission) = self.emission.filter(|emission| *emission != DVec3::ZERO) {
+            return Ok(Arc::new(DiffuseLight::with_solid(emission)));
+        }
+
+        if self.is_physically_based() {
+            return self.to_principled(base_dir);
+        }
+
+        if transparent {
+            return Ok(Arc::new(Dielectric::new(
+                self.refraction_index.unwrap_or(1.5),
+            )));
+        }
+
+        if metallic {
+            let exponent = self.specular_exponent.unwrap_or(0.0).max(0.0);
+            let fuzz = (2.0 / (exponent + 2.0)).sqrt();
+
+            return Ok(Arc::new(Metal::new(specular, fuzz)));
+        }
+
+        let albedo = texture(base_dir, self.diffuse_map.as_ref(), diffuse)?;
+
+        Ok(Arc::new(Lambertian::new(albedo)))
+    }
+
+    /// Whether the material uses any of the PBR extension keys.
+    fn is_physically_based(&self) -> bool {
+        self.roughness.is_some()
+            || self.roughness_map.is_some()
+            || self.metallic.is_some()
+            || self.metallic_map.is_some()
+            || self.sheen.is_some()
+            || self.clearcoat.is_some()
+            || self.clearcoat_roughness.is_some()
+    }
+
+    fn to_principled(&self, base_dir: &Path) -> Result<Arc<dyn Material>, ObjError> {
+        let diffuse = self.diffuse.unwrap_or(DVec3::splat(0.8));
+        let scalar = |map: Option<&String>, value: Option<f64>, default: f64| {
+            texture(base_dir, map, DVec3::splat(value.unwrap_or(default)))
+        };
+
+        let mut material = Principled::new(texture(base_dir, self.diffuse_map.as_ref(), diffuse)?)
+            .roughness_texture(scalar(self.roughness_map.as_ref(), self.roughness, 0.5)?)
+            .metallic_texture(scalar(self.metallic_map.as_ref(), self.metallic, 0.0)?)
+            .sheen(self.sheen.unwrap_or(0.0))
+            .clearcoat(self.clearcoat.unwrap_or(0.0))
+            .clearcoat_roughness(self.clearcoat_roughness.unwrap_or(0.03))
+            .transmission(1.0 - self.dissolve.unwrap_or(1.0));
+
+        if let Some(refraction_index) = self.refraction_index {
+            material = material.refraction_index(refraction_index);
+        }
+
+        Ok(Arc::new(material))
+    }
+}
+
+/// The image at `map` relative to `base_dir`, or `value` everywhere without a map.
+fn texture(base_dir: &Path, map: Option<&String>, value: DVec3) -> Result<Texture, ObjError> {
+    match map {
+        Some(map) => {
+            let path = base_dir.join(map);
+            let image = TextureImage::open(&path).map_err(|error| ObjError::Image(path, error))?;
+
+            Ok(Texture::Image(image))
+        }
+        None => Ok(Texture::Solid(value)),
+    }
+}
+
+struct LineParser<'a> {
+    tokens: SplitWhitespace<'a>,
+    file: &'a Path,
+    line: usize,
+}
+
+impl<'a> LineParser<'a> {
+    fn new(line: &'a str, file: &'a Path, number: usize) -> Self {
+        let content = line.split('#').next().unwrap_or_default();
+
+        Self {
+            tokens: content.split_whitespace(),
+            file,
+            line: number,
+        }
+    }
+
+    fn keyword(&mut self) -> Option<&'a str> {
+        self.tokens.next()
+    }
+
+    fn error(&self, message: &str) -> ObjError {
+        ObjError::Parse {
+            file: self.file.to_path_buf(),
+            line: self.line,
+            message: message.to_string(),
+        }
+    }
+
+    fn number<T: FromStr>(&mut self) -> Result<T, ObjError> {
+        let token = self
+            .tokens
+            .next()
+            .ok_or_else(|| self.error("expected a number"))?;
+
+        token
+            .parse()
+            .map_err(|_| self.error(&format!("invalid number '{token}'")))
+    }
+
+    fn optional_number(&mut self) -> Result<f64, ObjError> {
+        match self.tokens.clone().next() {
+            Some(_) => self.number(),
+            None => Ok(0.0),
+        }
+    }
+
+    fn dvec3(&mut self) -> Result<DVec3, ObjError> {
+        Ok(DVec3::new(self.number()?, self.number()?, self.number()?))
+    }
+
+    fn remaining(&mut self) -> Vec<&'a str> {
+        self.tokens.by_ref().collect()
+    }
+
+    /// The remainder of the line, which allows names containing spaces.
+    fn name(&mut self) -> Result<String, ObjError> {
+        let name = self.remaining().join(" ");
+
+        if name.is_empty() {
+            Err(self.error("expected a name"))
+        } else {
+            Ok(name)
+        }
+    }
+
+    /// The remaining tokens as separate names, like the libraries listed by `mtllib`.
+    fn names(&mut self) -> Result<Vec<String>, ObjError> {
+        let names = self.remaining();
+
+        if names.is_empty() {
+            Err(self.error("expected a name"))
+        } else {
+            Ok(names.into_iter().map(str::to_string).collect())
+        }
+    }
+
+    /// The file name of a texture map after its `-option value…` arguments, which are skipped
+    /// since textures are always applied as they are.
+    fn map_name(&mut self) -> Result<String, ObjError> {
+        while let Some(option) = self
+            .tokens
+            .clone()
+            .next()
+            .filter(|token| token.starts_with('-'))
+        {
+            self.tokens.next();
+
+            let (required, allowed) = match option {
+                "-blendu" | "-blendv" | "-boost" | "-cc" | "-clamp" | "-texres" | "-bm"
+                | "-imfchan" | "-type" => (1, 1),
+                "-mm" => (2, 2),
+                "-o" | "-s" | "-t" => (1, 3),
+                _ => return Err(self.error(&format!("unknown texture option '{option}'"))),
+            };
+
+            for count in 0..allowed {
+                let next = self.tokens.clone().next();
+
+                if count >= required && next.is_none_or(|token| token.parse::<f64>().is_err()) {
+                    break;
+                }
+                if next.is_none() {
+                    return Err(self.error(&format!("missing value for '{option}'")));
+                }
+
+                self.tokens.next();
+            }
+        }
+
+        self.name()
+    }
+
+    fn face_vertex(
+        &self,
+        vertex: &str,
+        position_count: usize,
+        uv_count: usize,
+        normal_count: usize,
+    ) -> Result<FaceVertex, ObjError> {
+        let mut parts = vertex.split('/');
+
+        let position = self.index(parts.next(), position_count)?;
+        let uv = self.optional_index(parts.next(), uv_count)?;
+        let normal = self.optional_index(parts.next(), normal_count)?;
+
+        match position {
+            Some(position) => Ok((position, uv, normal)),
+            None => Err(self.error(&format!("face vertex '{vertex}' has no position"))),
+        }
+    }
+
+    fn optional_index(&self, part: Option<&str>, count: usize) -> Result<Option<usize>, ObjError> {
+        match part {
+            Some(part) if !part.is_empty() => self.index(Some(part), count),
+            _ => Ok(None),
+        }
+    }
+
+    fn index(&self, part: Option<&str>, count: usize) -> Result<Option<usize>, ObjError> {
+        let Some(part) = part else {
+            return Ok(None);
+        };
+
+        let index: i64 = part
+            .parse()
+            .map_err(|_| self.error(&format!("invalid index '{part}'")))?;
+
+        let resolved = if index < 0 {
+            count as i64 + index
+        } else {
+            index - 1
+        };
+
+        if (0..count as i64).contains(&resolved) {
+            Ok(Some(resolved as usize))
+        } else {
+            Err(self.error(&format!("index {index} out of range")))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+    use crate::spectrum::SampledWavelengths;
+
+    #[test]
+    fn parse_obj() {
+        let source = "\
+mtllib scene.mtl
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+g quad
+usemtl red
+f 1/1 2/2 3/3 4/4
+g triangle
+f 1 -3 -2
+";
+        let obj = Obj::parse(source, Path::new("scene.obj")).unwrap();
+
+        assert_eq!(obj.material_libraries, vec!["scene.mtl"]);
+        assert_eq!(obj.meshes.len(), 2);
+
+        let quad = &obj.meshes[0];
+        assert_eq!(quad.material.as_deref(), Some("red"));
+        assert_eq!(quad.positions.len(), 4);
+        assert_eq!(quad.uvs.len(), 4);
+        assert!(quad.normals.is_empty());
+        assert_eq!(quad.indices, vec![[0, 1, 2], [0, 2, 3]]);
+
+        let triangle = &obj.meshes[1];
+        assert_eq!(
+            triangle.positions,
+            vec![DVec3::ZERO, DVec3::X, DVec3::new(1.0, 1.0, 0.0)]
+        );
+        assert!(triangle.uvs.is_empty());
+
+        let obj = Obj::parse("mtllib a.mtl  b.mtl\n", Path::new("scene.obj")).unwrap();
+        assert_eq!(obj.material_libraries, vec!["a.mtl", "b.mtl"]);
+
+        let error = Obj::parse("v 0 0 0\nf 1 1 x\n", Path::new("broken.obj")).unwrap_err();
+        assert!(matches!(error, ObjError::Parse { line: 2, .. }));
+        assert_eq!(error.to_string(), "broken.obj:2: invalid index 'x'");
+    }
+
+    #[test]
+    fn parse_mtl() {
+        let source = "\
+newmtl glass
+Ni 1.45
+d 0.2
+
+newmtl red
+Kd 0.8 0.1 0.1
+
+newmtl lamp
+Ke 4 4 4
+
+newmtl paint
+Kd 0.2 0.3 0.8
+Pr 0.4
+Pm 0.1
+Pc 1
+
+newmtl satin
+Kd 0.5 0.5 0.5
+Pcr 0.3
+";
+        let materials = Mtl::parse(source, Path::new("scene.mtl")).unwrap();
+
+        assert_eq!(materials.len(), 5);
+        assert_eq!(materials[0].refraction_index, Some(1.45));
+        assert_eq!(materials[0].dissolve, Some(0.2));
+        assert_eq!(materials[1].diffuse, Some(DVec3::new(0.8, 0.1, 0.1)));
+        assert_eq!(materials[2].emission, Some(DVec3::splat(4.0)));
+        let lamp = materials[2].to_material(Path::new(".")).unwrap();
+        assert_eq!(
+            lamp.emitted(DVec2::ZERO, DVec3::ZERO, SampledWavelengths::Rgb),
+            DVec3::splat(4.0)
+        );
+        let red = materials[1].to_material(Path::new(".")).unwrap();
+        assert_eq!(
+            red.emitted(DVec2::ZERO, DVec3::ZERO, SampledWavelengths::Rgb),
+            DVec3::ZERO
+        );
+        assert_eq!(materials[3].roughness, Some(0.4));
+        assert_eq!(materials[3].clearcoat, Some(1.0));
+        assert_eq!(materials[4].clearcoat_roughness, Some(0.3));
+        assert!(!materials[1].is_physically_based() && materials[3].is_physically_based());
+        assert!(materials[4].is_physically_based());
+
+        let error = Mtl::parse("Kd 1 1 1\n", Path::new("broken.mtl")).unwrap_err();
+        assert!(matches!(error, ObjError::Parse { line: 1, .. }));
+    }
+
+    #[test]
+    fn texture_map_options() {
+        let source = "\
+newmtl wood
+map_Kd -s 1 1 1 -o 0.5 -blendu off wood grain.png
+map_Pr -mm 0 1 -clamp on rough.png
+map_Pm metal.png
+";
+        let materials = Mtl::parse(source, Path::new("scene.mtl")).unwrap();
+
+        assert_eq!(materials[0].diffuse_map.as_deref(), Some("wood grain.png"));
+        assert_eq!(materials[0].roughness_map.as_deref(), Some("rough.png"));
+        assert_eq!(materials[0].metallic_map.as_deref(), Some("metal.png"));
+
+        for (line, message) in [
+            ("map_Kd -halo 2 tex.png", "unknown texture option '-halo'"),
+            ("map_Kd -mm 0", "missing value for '-mm'"),
+            ("map_Kd -s 1 1 1", "expected a name"),
+        ] {
+            let source = format!("newmtl broken\n{line}\n");
+            let error = Mtl::parse(&source, Path::new("broken.mtl")).unwrap_err();
+            assert_eq!(error.to_string(), format!("broken.mtl:2: {message}"));
+        }
+    }
+
+    #[test]
+    fn several_material_libraries() {
+        let directory =
+            env::temp_dir().join(format!("rust_tracer_{}_material_libraries", process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("red.mtl"), "newmtl red\nKd 1 0 0\n").unwrap();
+        fs::write(directory.join("blue.mtl"), "newmtl blue\nKd 0 0 1\n").unwrap();
+        fs::write(
+            directory.join("scene.obj"),
+            "mtllib red.mtl blue.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\n\
+             usemtl red\nf 1 2 3\nusemtl blue\nf 1 3 2\n",
+        )
+        .unwrap();
+
+        let meshes = load_obj(&directory.join("scene.obj")).unwrap();
+        assert_eq!(meshes.len(), 2);
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
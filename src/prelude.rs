@@ -1,12 +1,33 @@
 pub use crate::{
+    background::{Background, EnvironmentMap},
     bounding_volume,
-    bounding_volume::BoundingVolumeHierarchyNode,
-    camera::{builder::CameraBuilder, Camera, Image},
+    bounding_volume::{linear::LinearBoundingVolumeHierarchy, BoundingVolumeHierarchyNode},
+    camera::{builder::CameraBuilder, tone_mapping::ToneMapOperator, Camera, Image, SpectralMode},
+    color::TransferFunction,
+    constant_medium::ConstantMedium,
+    cuboid::Cuboid,
+    heterogeneous_medium::{Density, DensityGrid, HeterogeneousMedium, NoiseDensity},
     hittable::Hittable,
     hittable::HittableList,
-    material::{dielectric::Dielectric, lambertian::Lambertian, metal::Metal},
+    material::{
+        conductor::{ComplexIor, Conductor},
+        dielectric::{Dielectric, Dispersion, Fresnel},
+        diffuse_light::DiffuseLight,
+        henyey_greenstein::HenyeyGreenstein,
+        isotropic::Isotropic,
+        lambertian::Lambertian,
+        layered::Layered,
+        metal::Metal,
+        mix::MixMaterial,
+        oren_nayar::OrenNayar,
+        principled::Principled,
+        rough_dielectric::RoughDielectric,
+    },
+    quad::Quad,
     sphere::Sphere,
     texture::{Checker, PerlinTexture, Texture, TextureImage},
+    transform::Transform,
+    triangle::{Triangle, TriangleMesh},
 };
 
 pub use glam::{DVec2, DVec3};
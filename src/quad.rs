@@ -0,0 +1,176 @@
+use std::{ops::Range, sync::Arc};
+
+use crate::{
+    hittable::{AxisAlignedBoundingBox, HitRecord, Hittable},
+    material::Material,
+    ray::Ray,
+};
+use glam::{DVec2, DVec3};
+use rand::Rng;
+
+/// A planar parallelogram spanned by the edges `u` and `v` starting at `corner`.
+#[derive(Debug)]
+pub struct Quad {
+    corner: DVec3,
+    u: DVec3,
+    v: DVec3,
+    normal: DVec3,
+    d: f64,
+    w: DVec3,
+    bounding_box: AxisAlignedBoundingBox,
+    material: Arc<dyn Material>,
+}
+
+impl Quad {
+    pub fn new(corner: DVec3, u: DVec3, v: DVec3, material: Arc<dyn Material>) -> Self {
+        let n = u.cross(v);
+        let normal = n.normalize();
+        let d = normal.dot(corner);
+        let w = n / n.dot(n);
+
+        let bounding_box = AxisAlignedBoundingBox::from_boxes(
+            AxisAlignedBoundingBox::from_corners(corner, corner + u + v),
+            AxisAlignedBoundingBox::from_corners(corner + u, corner + v),
+        )
+        .padded();
+
+        Self {
+            corner,
+            u,
+            v,
+            normal,
+            d,
+            w,
+            bounding_box,
+            material,
+        }
+    }
+
+    pub fn corner(&self) -> DVec3 {
+        self.corner
+    }
+
+    pub fn u(&self) -> DVec3 {
+        self.u
+    }
+
+    pub fn v(&self) -> DVec3 {
+        self.v
+    }
+
+    pub fn area(&self) -> f64 {
+        self.u.cross(self.v).length()
+    }
+
+    fn planar_coordinates(&self, point: DVec3) -> Option<DVec2> {
+        let planar_hit = point - self.corner;
+        let alpha = self.w.dot(planar_hit.cross(self.v));
+        let beta = self.w.dot(self.u.cross(planar_hit));
+
+        if (0.0..=1.0).contains(&alpha) && (0.0..=1.0).contains(&beta) {
+            Some(DVec2::new(alpha, beta))
+        } else {
+            None
+        }
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, ray: Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let denominator = self.normal.dot(ray.direction);
+
+        if denominator.abs() < 1e-8 {
+            return None;
+        }
+
+        let t = (self.d - self.normal.dot(ray.origin)) / denominator;
+
+        if !t_range.contains(&t) {
+            return None;
+        }
+
+        let point = ray.at(t);
+        let uv = self.planar_coordinates(point)?;
+
+        Some(HitRecord::new(
+            ray,
+            point,
+            self.normal,
+            t,
+            uv,
+            self.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> &AxisAlignedBoundingBox {
+        &self.bounding_box
+    }
+
+    fn pdf_value(&self, origin: DVec3, direction: DVec3) -> f64 {
+        let Some(hit) = self.hit(Ray::new(origin, direction), 0.001..f64::INFINITY) else {
+            return 0.0;
+        };
+
+        let distance_squared = hit.t * hit.t * direction.length_squared();
+        let cosine = (direction.dot(self.normal) / direction.length()).abs();
+
+        distance_squared / (cosine * self.area())
+    }
+
+    fn random(&self, origin: DVec3) -> DVec3 {
+        let mut rand_thread = rand::thread_rng();
+        let point = self.corner
+            + rand_thread.gen_range(0.0..1.0) * self.u
+            + rand_thread.gen_range(0.0..1.0) * self.v;
+
+        point - origin
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hittable::assert_light_pdf, material::lambertian::Lambertian};
+
+    #[test]
+    fn quad_hit() {
+        let material = Arc::new(Lambertian::with_solid(DVec3::ONE));
+        let quad = Quad::new(DVec3::ZERO, DVec3::X * 2.0, DVec3::Y * 2.0, material);
+
+        let ray_front = Ray::new(DVec3::new(0.5, 1.0, 1.0), DVec3::NEG_Z);
+        let hit = quad.hit(ray_front, 0.0..f64::INFINITY).unwrap();
+
+        assert!(hit.front_face);
+        assert_eq!(hit.t, 1.0);
+        assert_eq!(hit.normal, DVec3::Z);
+        assert_eq!(hit.uv, DVec2::new(0.25, 0.5));
+
+        let ray_back = Ray::new(DVec3::new(1.0, 1.0, -1.0), DVec3::Z);
+        let hit = quad.hit(ray_back, 0.0..f64::INFINITY).unwrap();
+
+        assert!(!hit.front_face);
+        assert_eq!(hit.normal, DVec3::NEG_Z);
+
+        let ray_outside = Ray::new(DVec3::new(2.5, 1.0, 1.0), DVec3::NEG_Z);
+        assert!(quad.hit(ray_outside, 0.0..f64::INFINITY).is_none());
+
+        let ray_parallel = Ray::new(DVec3::new(0.5, 1.0, 1.0), DVec3::X);
+        assert!(quad.hit(ray_parallel, 0.0..f64::INFINITY).is_none());
+
+        assert!(quad.bounding_box().hit(ray_front, 0.0..f64::INFINITY));
+    }
+
+    #[test]
+    fn quad_pdf() {
+        let material = Arc::new(Lambertian::with_solid(DVec3::ONE));
+        let quad = Quad::new(
+            DVec3::new(-1.0, 1.0, -0.5),
+            DVec3::X * 2.0,
+            DVec3::Z,
+            material,
+        );
+
+        assert_light_pdf(&quad, DVec3::ZERO);
+        assert_light_pdf(&quad, DVec3::new(2.0, 3.0, 0.0));
+    }
+}
@@ -1,10 +1,14 @@
 use glam::DVec3;
 
+use crate::spectrum::SampledWavelengths;
+
 #[derive(Default, Debug, Clone, Copy)]
 pub struct Ray {
     pub origin: DVec3,
     pub direction: DVec3,
     pub time: f64,
+    /// What the channels of the colors along the ray stand for.
+    pub wavelengths: SampledWavelengths,
 }
 
 impl Ray {
@@ -13,14 +17,27 @@ impl Ray {
             origin,
             direction,
             time: 0.0,
+            wavelengths: SampledWavelengths::Rgb,
         }
     }
 
+    /// A camera ray, rays leaving a hit are made with [`Ray::spawn`].
     pub fn new_with_time(origin: DVec3, direction: DVec3, time: f64) -> Self {
         Self {
             origin,
             direction,
             time,
+            wavelengths: SampledWavelengths::Rgb,
+        }
+    }
+
+    /// A ray continuing the path of this one, at the same time and with the same wavelengths
+    /// so its colors stay in the same channels.
+    pub fn spawn(&self, origin: DVec3, direction: DVec3) -> Self {
+        Self {
+            origin,
+            direction,
+            ..*self
         }
     }
 
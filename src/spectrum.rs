@@ -0,0 +1,390 @@
+use std::sync::OnceLock;
+
+use glam::{DMat3, DVec3};
+
+/// Shortest wavelength in nanometers sampled by spectral rendering.
+pub const WAVELENGTH_MIN: f64 = 380.0;
+/// Longest wavelength in nanometers sampled by spectral rendering.
+pub const WAVELENGTH_MAX: f64 = 780.0;
+
+/// The CIE 1931 2° color matching functions at `wavelength` in nanometers, using the multi-lobe
+/// Gaussian fit of Wyman, Sloan and Shirley.
+pub fn cie_xyz(wavelength: f64) -> DVec3 {
+    let lobe = |mean: f64, below: f64, above: f64| {
+        let deviation = if wavelength < mean { below } else { above };
+        let x = (wavelength - mean) / deviation;
+
+        (-0.5 * x * x).exp()
+    };
+
+    DVec3::new(
+        1.056 * lobe(599.8, 37.9, 31.0) + 0.362 * lobe(442.0, 16.0, 26.7)
+            - 0.065 * lobe(501.1, 20.4, 26.2),
+        0.821 * lobe(568.8, 46.9, 40.5) + 0.286 * lobe(530.9, 16.3, 31.1),
+        1.217 * lobe(437.0, 11.8, 36.0) + 0.681 * lobe(459.0, 26.0, 13.8),
+    )
+}
+
+/// Converts CIE XYZ to linear sRGB with a D65 white point.
+pub fn xyz_to_linear_srgb(xyz: DVec3) -> DVec3 {
+    let matrix = DMat3::from_cols_array(&[
+        3.2404542, -0.9692660, 0.0556434, -1.5371385, 1.8760108, -0.2040259, -0.4985314, 0.0415560,
+        1.0572252,
+    ]);
+
+    matrix * xyz
+}
+
+/// Maps a uniform random number to a wavelength in the visible range.
+pub fn sample_wavelength(u: f64) -> f64 {
+    WAVELENGTH_MIN + u * (WAVELENGTH_MAX - WAVELENGTH_MIN)
+}
+
+/// Linear sRGB response to a single wavelength, divided by its pdf and normalized so that an
+/// equal energy spectrum averages to white.
+///
+/// Multiplying the radiance of a path by it turns a uniformly sampled wavelength into an
+/// unbiased estimate of the color. Single samples are tinted, but paths which don't depend on
+/// the wavelength average out to their own color.
+pub fn wavelength_weight(wavelength: f64) -> DVec3 {
+    static WHITE: OnceLock<DVec3> = OnceLock::new();
+
+    let white = WHITE.get_or_init(|| {
+        let steps = 4000;
+
+        (0..steps)
+            .map(|step| {
+                xyz_to_linear_srgb(cie_xyz(sample_wavelength(
+                    (step as f64 + 0.5) / steps as f64,
+                )))
+            })
+            .sum::<DVec3>()
+            / steps as f64
+    });
+
+    xyz_to_linear_srgb(cie_xyz(wavelength)) / *white
+}
+
+/// The wavelengths one camera sample carries, which decide what the channels of every color
+/// along its path stand for.
+///
+/// Colors stay [`DVec3`]s everywhere. For spectral samples each channel holds the value of a
+/// spectrum at one of the wavelengths instead of red, green or blue, so exponentials, Fresnel
+/// terms and ratios of them are evaluated per wavelength. RGB inputs are upsampled where they
+/// are looked up and the values only turn back into a color at the film, through CIE XYZ.
+/// Rays leaving a hit are made with `Ray::spawn` to keep them.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum SampledWavelengths {
+    /// Colors are red, green and blue.
+    #[default]
+    Rgb,
+    /// Colors stay RGB while dispersive materials refract at this wavelength in nanometers.
+    Hero(f64),
+    /// Colors hold values at these wavelengths in nanometers. The first one is the hero
+    /// wavelength dispersive materials refract at.
+    Spectral(DVec3),
+}
+
+impl SampledWavelengths {
+    /// Three wavelengths evenly spaced over the visible range from the one `u` maps to, so a
+    /// single sample covers the whole spectrum.
+    pub fn stratified(u: f64) -> Self {
+        let wavelength = |offset: f64| sample_wavelength((u + offset).fract());
+
+        Self::Spectral(DVec3::new(
+            wavelength(0.0),
+            wavelength(1.0 / 3.0),
+            wavelength(2.0 / 3.0),
+        ))
+    }
+
+    /// The wavelength dispersive materials refract at, if any.
+    pub fn hero(&self) -> Option<f64> {
+        match self {
+            Self::Rgb => None,
+            Self::Hero(wavelength) => Some(*wavelength),
+            Self::Spectral(wavelengths) => Some(wavelengths.x),
+        }
+    }
+
+    /// Upsamples a reflectance, albedo or coefficient with Smits' method, RGB stays unchanged.
+    /// Grays stay exactly flat, so gray media and filters don't pick up the ripple of the basis.
+    pub fn upsample(&self, rgb: DVec3) -> DVec3 {
+        match self {
+            Self::Spectral(_) if rgb.x == rgb.y && rgb.y == rgb.z => rgb.max(DVec3::ZERO),
+            Self::Spectral(wavelengths) => {
+                let spectrum = RgbSpectrum::new(rgb);
+
+                DVec3::from_array(
+                    wavelengths
+                        .to_array()
+                        .map(|wavelength| spectrum.value(wavelength)),
+                )
+            }
+            _ => rgb,
+        }
+    }
+
+    /// Upsamples emitted radiance with Smits' method times the CIE D65 illuminant, so a white
+    /// light has the spectrum of the sRGB white point rather than an equal energy one.
+    pub fn upsample_illuminant(&self, rgb: DVec3) -> DVec3 {
+        match self {
+            Self::Spectral(wavelengths) => {
+                self.upsample(rgb) * DVec3::from_array(wavelengths.to_array().map(d65))
+            }
+            _ => rgb,
+        }
+    }
+
+    /// Weights for the channels after a bounce that only holds for the hero wavelength, such as
+    /// a refraction by a dispersive material. The other wavelengths are terminated and the hero
+    /// carries their share.
+    pub fn terminate_secondary(&self) -> DVec3 {
+        match self {
+            Self::Spectral(_) => DVec3::new(3.0, 0.0, 0.0),
+            _ => DVec3::ONE,
+        }
+    }
+
+    /// Turns the radiance a sample carried into linear sRGB. Spectral values are converted
+    /// with the color matching functions, normalized so a white light averages to white.
+    pub fn to_rgb(&self, values: DVec3) -> DVec3 {
+        match self {
+            Self::Rgb => values,
+            Self::Hero(wavelength) => values * wavelength_weight(*wavelength),
+            Self::Spectral(wavelengths) => {
+                static WHITE: OnceLock<DVec3> = OnceLock::new();
+
+                let white = WHITE.get_or_init(|| {
+                    let steps = 4000;
+
+                    (0..steps)
+                        .map(|step| {
+                            let wavelength = sample_wavelength((step as f64 + 0.5) / steps as f64);
+
+                            xyz_to_linear_srgb(cie_xyz(wavelength) * d65(wavelength))
+                        })
+                        .sum::<DVec3>()
+                        / steps as f64
+                });
+
+                let xyz = (0..3)
+                    .map(|index| values[index] * cie_xyz(wavelengths[index]))
+                    .sum::<DVec3>()
+                    / 3.0;
+
+                xyz_to_linear_srgb(xyz) / *white
+            }
+        }
+    }
+}
+
+/// A smooth reflectance or emission spectrum upsampled from a linear RGB color with Smits'
+/// method, which builds it from white, the primaries and their complements.
+#[derive(Debug, Clone, Copy)]
+pub struct RgbSpectrum {
+    rgb: DVec3,
+}
+
+impl RgbSpectrum {
+    pub fn new(rgb: DVec3) -> Self {
+        Self {
+            rgb: rgb.max(DVec3::ZERO),
+        }
+    }
+
+    /// The value of the spectrum at `wavelength` in nanometers.
+    pub fn value(&self, wavelength: f64) -> f64 {
+        let basis = |table: &[f64; SMITS_BINS]| smits_basis(table, wavelength);
+        let DVec3 { x: r, y: g, z: b } = self.rgb;
+
+        if r <= g && r <= b {
+            r * basis(&SMITS_WHITE)
+                + if g <= b {
+                    (g - r) * basis(&SMITS_CYAN) + (b - g) * basis(&SMITS_BLUE)
+                } else {
+                    (b - r) * basis(&SMITS_CYAN) + (g - b) * basis(&SMITS_GREEN)
+                }
+        } else if g <= r && g <= b {
+            g * basis(&SMITS_WHITE)
+                + if r <= b {
+                    (r - g) * basis(&SMITS_MAGENTA) + (b - r) * basis(&SMITS_BLUE)
+                } else {
+                    (b - g) * basis(&SMITS_MAGENTA) + (r - b) * basis(&SMITS_RED)
+                }
+        } else {
+            b * basis(&SMITS_WHITE)
+                + if r <= g {
+                    (r - b) * basis(&SMITS_YELLOW) + (g - r) * basis(&SMITS_GREEN)
+                } else {
+                    (g - b) * basis(&SMITS_YELLOW) + (r - g) * basis(&SMITS_RED)
+                }
+        }
+    }
+}
+
+const SMITS_BINS: usize = 10;
+const SMITS_MIN: f64 = 380.0;
+const SMITS_MAX: f64 = 720.0;
+
+const SMITS_WHITE: [f64; SMITS_BINS] = [
+    1.0000, 1.0000, 0.9999, 0.9993, 0.9992, 0.9998, 1.0000, 1.0000, 1.0000, 1.0000,
+];
+const SMITS_CYAN: [f64; SMITS_BINS] = [
+    0.9710, 0.9426, 1.0007, 1.0007, 1.0007, 1.0007, 0.1564, 0.0000, 0.0000, 0.0000,
+];
+const SMITS_MAGENTA: [f64; SMITS_BINS] = [
+    1.0000, 1.0000, 0.9685, 0.2229, 0.0000, 0.0458, 0.8369, 1.0000, 1.0000, 0.9959,
+];
+const SMITS_YELLOW: [f64; SMITS_BINS] = [
+    0.0001, 0.0000, 0.1088, 0.6651, 1.0000, 1.0000, 0.9996, 0.9586, 0.9685, 0.9840,
+];
+const SMITS_RED: [f64; SMITS_BINS] = [
+    0.1012, 0.0515, 0.0000, 0.0000, 0.0000, 0.0000, 0.8325, 1.0149, 1.0149, 1.0149,
+];
+const SMITS_GREEN: [f64; SMITS_BINS] = [
+    0.0000, 0.0000, 0.0273, 0.7937, 1.0000, 0.9418, 0.1719, 0.0000, 0.0000, 0.0025,
+];
+const SMITS_BLUE: [f64; SMITS_BINS] = [
+    1.0000, 1.0000, 0.8916, 0.3323, 0.0000, 0.0000, 0.0003, 0.0369, 0.0483, 0.0496,
+];
+
+/// Linearly interpolates one of Smits' tables, whose bins are spread evenly over 380 to 720
+/// nanometers and held constant beyond.
+fn smits_basis(table: &[f64; SMITS_BINS], wavelength: f64) -> f64 {
+    let position = ((wavelength - SMITS_MIN) / (SMITS_MAX - SMITS_MIN) * (SMITS_BINS - 1) as f64)
+        .clamp(0.0, (SMITS_BINS - 1) as f64);
+    let index = (position as usize).min(SMITS_BINS - 2);
+    let fraction = position - index as f64;
+
+    table[index] * (1.0 - fraction) + table[index + 1] * fraction
+}
+
+/// The CIE standard illuminant D65 in 10 nanometer steps over the visible range, relative to
+/// its value at 560 nanometers.
+const D65: [f64; 41] = [
+    49.98, 54.65, 82.75, 91.49, 93.43, 86.68, 104.86, 117.01, 117.81, 114.86, 115.92, 108.81,
+    109.35, 107.80, 104.79, 107.69, 104.41, 104.05, 100.00, 96.33, 95.79, 88.69, 90.01, 89.60,
+    87.70, 83.29, 83.70, 80.03, 80.21, 82.28, 78.28, 69.72, 71.61, 74.35, 61.60, 69.89, 75.09,
+    63.59, 46.42, 66.81, 63.38,
+];
+
+/// Linearly interpolates [`D65`], held constant beyond the visible range.
+fn d65(wavelength: f64) -> f64 {
+    let position = ((wavelength - WAVELENGTH_MIN) / 10.0).clamp(0.0, (D65.len() - 1) as f64);
+    let index = (position as usize).min(D65.len() - 2);
+    let fraction = position - index as f64;
+
+    (D65[index] * (1.0 - fraction) + D65[index + 1] * fraction) / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_matching() {
+        let peak = cie_xyz(555.0);
+        assert!((peak.y - 1.0).abs() < 0.01, "{peak}");
+        assert!(cie_xyz(WAVELENGTH_MAX).length() < 1e-3);
+
+        let red = wavelength_weight(650.0);
+        assert!(red.x > red.y && red.x > red.z);
+        let blue = wavelength_weight(450.0);
+        assert!(blue.z > blue.x && blue.z > blue.y);
+
+        let steps = 1000;
+        let average = (0..steps)
+            .map(|step| wavelength_weight(sample_wavelength(step as f64 / steps as f64)))
+            .sum::<DVec3>()
+            / steps as f64;
+        assert!(average.abs_diff_eq(DVec3::ONE, 1e-2), "{average}");
+    }
+
+    #[test]
+    fn rgb_upsampling() {
+        let steps = 2000;
+        let round_trip = |rgb: DVec3| {
+            let spectrum = RgbSpectrum::new(rgb);
+
+            (0..steps)
+                .map(|step| {
+                    let wavelength = sample_wavelength((step as f64 + 0.5) / steps as f64);
+                    spectrum.value(wavelength) * wavelength_weight(wavelength)
+                })
+                .sum::<DVec3>()
+                / steps as f64
+        };
+
+        let white = RgbSpectrum::new(DVec3::ONE);
+        assert!((380..=780).all(|wavelength| (white.value(wavelength as f64) - 1.0).abs() < 1e-3));
+        assert!(round_trip(DVec3::splat(0.5)).abs_diff_eq(DVec3::splat(0.5), 0.01));
+
+        for rgb in [
+            DVec3::new(0.8, 0.2, 0.1),
+            DVec3::new(0.1, 0.6, 0.2),
+            DVec3::new(0.2, 0.3, 0.9),
+            DVec3::new(0.9, 0.8, 0.2),
+        ] {
+            let result = round_trip(rgb);
+            assert!(result.abs_diff_eq(rgb, 0.1), "{rgb} {result}");
+        }
+    }
+
+    #[test]
+    fn sampled_wavelengths() {
+        let SampledWavelengths::Spectral(wavelengths) = SampledWavelengths::stratified(0.9) else {
+            unreachable!()
+        };
+        assert_eq!(
+            SampledWavelengths::Spectral(wavelengths).hero(),
+            Some(wavelengths.x)
+        );
+        assert!((wavelengths.x - sample_wavelength(0.9)).abs() < 1e-9);
+        assert!((wavelengths.y - sample_wavelength(0.9 + 1.0 / 3.0 - 1.0)).abs() < 1e-9);
+
+        let steps = 2000;
+        let film = |color: &dyn Fn(SampledWavelengths) -> DVec3| {
+            (0..steps)
+                .map(|step| {
+                    let wavelengths =
+                        SampledWavelengths::stratified((step as f64 + 0.5) / steps as f64);
+
+                    wavelengths.to_rgb(color(wavelengths))
+                })
+                .sum::<DVec3>()
+                / steps as f64
+        };
+
+        // A white light is D65, which the film maps back to white.
+        let white = film(&|wavelengths| wavelengths.upsample_illuminant(DVec3::ONE));
+        assert!(white.abs_diff_eq(DVec3::ONE, 1e-3), "{white}");
+        let gray = SampledWavelengths::stratified(0.3).upsample(DVec3::splat(0.5));
+        assert_eq!(gray, DVec3::splat(0.5));
+
+        for rgb in [
+            DVec3::new(0.8, 0.2, 0.1),
+            DVec3::new(0.1, 0.6, 0.2),
+            DVec3::new(0.2, 0.3, 0.9),
+            DVec3::new(0.9, 0.8, 0.2),
+        ] {
+            let light = film(&|wavelengths| wavelengths.upsample_illuminant(rgb));
+            assert!(light.abs_diff_eq(rgb, 0.1), "{rgb} {light}");
+
+            let reflected = film(&|wavelengths| {
+                wavelengths.upsample(rgb) * wavelengths.upsample_illuminant(DVec3::ONE)
+            });
+            assert!(reflected.abs_diff_eq(rgb, 0.1), "{rgb} {reflected}");
+        }
+
+        // Colors of the other kinds stay RGB.
+        let rgb = DVec3::new(0.8, 0.2, 0.1);
+        for wavelengths in [SampledWavelengths::Rgb, SampledWavelengths::Hero(500.0)] {
+            assert_eq!(wavelengths.upsample(rgb), rgb);
+            assert_eq!(wavelengths.upsample_illuminant(rgb), rgb);
+            assert_eq!(wavelengths.terminate_secondary(), DVec3::ONE);
+        }
+        assert_eq!(SampledWavelengths::Rgb.to_rgb(rgb), rgb);
+        assert_eq!(SampledWavelengths::Rgb.hero(), None);
+    }
+}
@@ -6,6 +6,7 @@ use crate::{
     ray::Ray,
 };
 use glam::{DVec2, DVec3};
+use rand::Rng;
 
 #[derive(Debug)]
 pub struct Sphere {
@@ -117,4 +118,59 @@ impl Hittable for Sphere {
     fn bounding_box(&self) -> &AxisAlignedBoundingBox {
         &self.bounding_box
     }
+
+    /// Moving spheres are sampled at their start position, only stationary ones make
+    /// correct lights.
+    fn pdf_value(&self, origin: DVec3, direction: DVec3) -> f64 {
+        if self
+            .hit(Ray::new(origin, direction), 0.001..f64::INFINITY)
+            .is_none()
+        {
+            return 0.0;
+        }
+
+        let distance_squared = (self.start_position - origin).length_squared();
+        let cos_theta_max = (1.0 - self.radius * self.radius / distance_squared)
+            .max(0.0)
+            .sqrt();
+        let solid_angle = 2.0 * PI * (1.0 - cos_theta_max);
+
+        solid_angle.recip()
+    }
+
+    fn random(&self, origin: DVec3) -> DVec3 {
+        let direction = self.start_position - origin;
+        let distance_squared = direction.length_squared();
+
+        let mut rand_thread = rand::thread_rng();
+        let r1: f64 = rand_thread.gen_range(0.0..1.0);
+        let r2: f64 = rand_thread.gen_range(0.0..1.0);
+
+        let cos_theta_max = (1.0 - self.radius * self.radius / distance_squared)
+            .max(0.0)
+            .sqrt();
+        let z = 1.0 + r2 * (cos_theta_max - 1.0);
+        let phi = 2.0 * PI * r1;
+        let sin_theta = (1.0 - z * z).sqrt();
+
+        let w = direction.normalize();
+        let (u, v) = w.any_orthonormal_pair();
+
+        u * phi.cos() * sin_theta + v * phi.sin() * sin_theta + w * z
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hittable::assert_light_pdf, material::lambertian::Lambertian};
+
+    #[test]
+    fn sphere_pdf() {
+        let material = Arc::new(Lambertian::with_solid(DVec3::ONE));
+        let sphere = Sphere::stationary(DVec3::new(0.0, 2.0, 0.0), 1.0, material);
+
+        assert_light_pdf(&sphere, DVec3::ZERO);
+        assert_light_pdf(&sphere, DVec3::new(3.0, -1.0, 2.0));
+    }
 }
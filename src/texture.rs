@@ -1,9 +1,11 @@
 use std::path::Path;
 
 use glam::{DVec2, DVec3};
-use image::{io::Reader as ImageReader, DynamicImage, GenericImageView};
+use image::{io::Reader as ImageReader, DynamicImage, GenericImageView, ImageResult};
 use rand::Rng;
 
+use crate::spectrum::SampledWavelengths;
+
 pub(crate) trait Sample {
     fn sample(&self, uv: DVec2, position: DVec3) -> DVec3;
 }
@@ -16,6 +18,19 @@ pub enum Texture {
     Perlin(PerlinTexture),
 }
 
+impl Texture {
+    /// The color at `uv` and `position` as seen by a path carrying `wavelengths`, see
+    /// [`SampledWavelengths::upsample`]. Scalar parameters use the plain RGB sample.
+    pub(crate) fn sample_spectrum(
+        &self,
+        uv: DVec2,
+        position: DVec3,
+        wavelengths: SampledWavelengths,
+    ) -> DVec3 {
+        wavelengths.upsample(self.sample(uv, position))
+    }
+}
+
 impl Sample for Texture {
     fn sample(&self, uv: DVec2, position: DVec3) -> DVec3 {
         match self {
@@ -72,8 +87,12 @@ pub struct TextureImage {
 
 impl TextureImage {
     pub fn from_file(file: &Path) -> Self {
-        let data = ImageReader::open(file).unwrap().decode().unwrap();
-        Self { data }
+        Self::open(file).unwrap()
+    }
+
+    pub fn open(file: &Path) -> ImageResult<Self> {
+        let data = ImageReader::open(file)?.decode()?;
+        Ok(Self { data })
     }
 }
 
@@ -96,6 +115,12 @@ pub struct PerlinTexture {
     scale: f64,
 }
 
+impl Default for PerlinTexture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PerlinTexture {
     pub fn new() -> Self {
         Self::new_scaled(1.0)
@@ -104,7 +129,7 @@ impl PerlinTexture {
     pub fn new_scaled(scale: f64) -> Self {
         Self {
             perlin: PerlinNoise::new(),
-            scale: scale,
+            scale,
         }
     }
 }
@@ -116,7 +141,7 @@ impl Sample for PerlinTexture {
 }
 
 #[derive(Debug)]
-struct PerlinNoise {
+pub(crate) struct PerlinNoise {
     random_vectors: Vec<DVec3>,
     permute_x: Vec<i32>,
     permute_y: Vec<i32>,
@@ -126,7 +151,7 @@ struct PerlinNoise {
 impl PerlinNoise {
     pub fn new() -> Self {
         let mut rand_thread = rand::thread_rng();
-        let random_vectors = (0..256).into_iter().map(|_| {
+        let random_vectors = (0..256).map(|_| {
             DVec3::new(rand_thread.gen_range(-1.0..1.0), rand_thread.gen_range(-1.0..1.0), rand_thread.gen_range(-1.0..1.0))   
         }).collect();
 
@@ -137,6 +162,7 @@ impl PerlinNoise {
         Self { random_vectors, permute_x, permute_y, permute_z }
     }
 
+    #[allow(clippy::needless_range_loop)]
     pub fn noise(&self, position: DVec3) -> f64 {
         let x = position.x - position.x.floor();
         let y = position.y - position.y.floor();
@@ -166,6 +192,22 @@ impl PerlinNoise {
         Self::trilinear_interpolation(&c, x, y, z)
     }
 
+    /// Magnitude of `depth` summed octaves of noise, each at double the frequency and half the
+    /// weight of the previous one.
+    pub fn turbulence(&self, position: DVec3, depth: u32) -> f64 {
+        let mut accumulation = 0.0;
+        let mut position = position;
+        let mut weight = 1.0;
+
+        for _ in 0..depth {
+            accumulation += weight * self.noise(position);
+            weight *= 0.5;
+            position *= 2.0;
+        }
+
+        accumulation.abs()
+    }
+
     fn generate_permute() -> Vec<i32> {
         let mut elements: Vec<i32> = (0..256).collect();
         Self::permute(256, &mut elements);
@@ -177,12 +219,11 @@ impl PerlinNoise {
         let mut rand_thread = rand::thread_rng();
         for i in (1..n as usize).rev() {
             let target = rand_thread.gen_range(0..i);
-            let tmp = elements[i];
-            elements[i] = elements[target];
-            elements[target] = tmp;
+            elements.swap(i, target);
         }
     }
 
+    #[allow(clippy::needless_range_loop)]
     fn trilinear_interpolation(c: &[[[DVec3; 2]; 2]; 2], x: f64, y: f64, z: f64) -> f64 {
         let mut accummulation = 0.0;
 
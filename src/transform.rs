@@ -0,0 +1,260 @@
+use std::{ops::Range, sync::Arc};
+
+use glam::{DAffine3, DMat3, DQuat, DVec3};
+
+use crate::{
+    hittable::{AxisAlignedBoundingBox, HitRecord, Hittable},
+    ray::Ray,
+};
+
+/// Places a shared object in the world with an affine object-to-world matrix.
+///
+/// Rays are moved into object space for intersection, so the same geometry can be instanced
+/// many times without being copied.
+#[derive(Debug)]
+pub struct Transform {
+    object: Arc<dyn Hittable>,
+    object_to_world: DAffine3,
+    world_to_object: DAffine3,
+    normal_matrix: DMat3,
+    bounding_box: AxisAlignedBoundingBox,
+}
+
+impl Transform {
+    pub fn new(object: Arc<dyn Hittable>, object_to_world: DAffine3) -> Self {
+        let world_to_object = object_to_world.inverse();
+        let normal_matrix = world_to_object.matrix3.transpose();
+        let bounding_box = transformed_box(object.bounding_box(), &object_to_world);
+
+        Self {
+            object,
+            object_to_world,
+            world_to_object,
+            normal_matrix,
+            bounding_box,
+        }
+    }
+
+    pub fn translate(object: Arc<dyn Hittable>, offset: DVec3) -> Self {
+        Self::new(object, DAffine3::from_translation(offset))
+    }
+
+    /// Rotates by `angle` degrees around `axis`.
+    pub fn rotate(object: Arc<dyn Hittable>, axis: DVec3, angle: f64) -> Self {
+        Self::new(
+            object,
+            DAffine3::from_axis_angle(axis.normalize(), angle.to_radians()),
+        )
+    }
+
+    pub fn scale(object: Arc<dyn Hittable>, scale: DVec3) -> Self {
+        Self::new(object, DAffine3::from_scale(scale))
+    }
+
+    /// Scales first, then rotates, then translates, with the rotation given in degrees around
+    /// `axis`.
+    pub fn from_scale_rotation_translation(
+        object: Arc<dyn Hittable>,
+        scale: DVec3,
+        axis: DVec3,
+        angle: f64,
+        translation: DVec3,
+    ) -> Self {
+        Self::new(
+            object,
+            DAffine3::from_scale_rotation_translation(
+                scale,
+                DQuat::from_axis_angle(axis.normalize(), angle.to_radians()),
+                translation,
+            ),
+        )
+    }
+}
+
+impl Hittable for Transform {
+    fn hit(&self, ray: Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        // The direction isn't renormalized, so `t` is the same in both spaces.
+        let object_ray = ray.spawn(
+            self.world_to_object.transform_point3(ray.origin),
+            self.world_to_object.transform_vector3(ray.direction),
+        );
+
+        let mut hit = self.object.hit(object_ray, t_range)?;
+
+        hit.point = self.object_to_world.transform_point3(hit.point);
+        hit.normal = (self.normal_matrix * hit.normal).normalize();
+
+        Some(hit)
+    }
+
+    fn bounding_box(&self) -> &AxisAlignedBoundingBox {
+        &self.bounding_box
+    }
+
+    fn pdf_value(&self, origin: DVec3, direction: DVec3) -> f64 {
+        let object_origin = self.world_to_object.transform_point3(origin);
+        let object_direction = self.world_to_object.transform_vector3(direction);
+
+        let Some(hit) = self.object.hit(
+            Ray::new(object_origin, object_direction),
+            0.001..f64::INFINITY,
+        ) else {
+            return 0.0;
+        };
+
+        let object_pdf = self.object.pdf_value(object_origin, object_direction);
+
+        // Converts the solid angle density between both spaces through the area density.
+        let object_offset = hit.t * object_direction;
+        let world_offset = hit.t * direction;
+        let transformed_normal = self.normal_matrix * hit.normal;
+        let object_cosine = object_offset.normalize().dot(hit.normal).abs();
+        let world_cosine = world_offset
+            .normalize()
+            .dot(transformed_normal.normalize())
+            .abs();
+
+        let area_scale =
+            transformed_normal.length() * self.object_to_world.matrix3.determinant().abs();
+
+        if object_cosine == 0.0 || world_cosine == 0.0 {
+            return 0.0;
+        }
+
+        object_pdf * object_cosine / object_offset.length_squared() * world_offset.length_squared()
+            / world_cosine
+            / area_scale
+    }
+
+    fn random(&self, origin: DVec3) -> DVec3 {
+        let object_origin = self.world_to_object.transform_point3(origin);
+        let object_direction = self.object.random(object_origin);
+
+        self.object_to_world.transform_vector3(object_direction)
+    }
+}
+
+fn transformed_box(
+    bounding_box: &AxisAlignedBoundingBox,
+    transform: &DAffine3,
+) -> AxisAlignedBoundingBox {
+    let min = bounding_box.min();
+    let max = bounding_box.max();
+
+    let corners = (0..8).map(|corner| {
+        DVec3::new(
+            if corner & 1 == 0 { min.x } else { max.x },
+            if corner & 2 == 0 { min.y } else { max.y },
+            if corner & 4 == 0 { min.z } else { max.z },
+        )
+    });
+
+    let (min, max) = corners
+        .map(|corner| transform.transform_point3(corner))
+        .fold(
+            (DVec3::INFINITY, DVec3::NEG_INFINITY),
+            |(min, max), corner| (min.min(corner), max.max(corner)),
+        );
+
+    AxisAlignedBoundingBox::from_corners(min, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constant_medium::ConstantMedium, material::lambertian::Lambertian,
+        spectrum::SampledWavelengths, sphere::Sphere,
+    };
+
+    #[test]
+    fn transformed_sphere() {
+        let material = Arc::new(Lambertian::with_solid(DVec3::ONE));
+        let unit_sphere: Arc<dyn Hittable> =
+            Arc::new(Sphere::stationary(DVec3::ZERO, 1.0, material.clone()));
+        let transform = Transform::from_scale_rotation_translation(
+            unit_sphere,
+            DVec3::splat(2.0),
+            DVec3::Y,
+            45.0,
+            DVec3::new(0.0, 0.0, -5.0),
+        );
+        let reference = Sphere::stationary(DVec3::new(0.0, 0.0, -5.0), 2.0, material);
+
+        let ray = Ray::new(DVec3::new(0.5, 0.5, 0.0), DVec3::NEG_Z);
+        let hit = transform.hit(ray, 0.001..f64::INFINITY).unwrap();
+        let expected = reference.hit(ray, 0.001..f64::INFINITY).unwrap();
+
+        assert!((hit.t - expected.t).abs() < 1e-9);
+        assert!(hit.point.abs_diff_eq(expected.point, 1e-9));
+        assert!(hit.normal.abs_diff_eq(expected.normal, 1e-9));
+        assert!(hit.front_face);
+
+        let bounding_box = transform.bounding_box();
+        assert!(bounding_box.min().cmple(DVec3::new(-2.0, -2.0, -7.0)).all());
+        assert!(bounding_box.max().cmpge(DVec3::new(2.0, 2.0, -3.0)).all());
+
+        let direction = DVec3::new(0.1, 0.2, -1.0);
+        let pdf = transform.pdf_value(DVec3::ZERO, direction);
+        let expected_pdf = reference.pdf_value(DVec3::ZERO, direction);
+
+        assert!((pdf - expected_pdf).abs() < 1e-6 * expected_pdf);
+    }
+
+    #[test]
+    fn transformed_medium() {
+        let boundary = Arc::new(Sphere::stationary(
+            DVec3::ZERO,
+            1.0,
+            Arc::new(Lambertian::with_solid(DVec3::ONE)),
+        ));
+        let sigma_a = DVec3::new(0.5, 1.0, 1.5);
+        let medium: Arc<dyn Hittable> = Arc::new(ConstantMedium::from_coefficients(
+            boundary,
+            sigma_a,
+            DVec3::ZERO,
+        ));
+        let transform = Transform::new(medium.clone(), DAffine3::IDENTITY);
+
+        let wavelengths = SampledWavelengths::stratified(0.1);
+        let ray = Ray {
+            wavelengths,
+            ..Ray::new(DVec3::new(0.0, 0.0, -5.0), DVec3::Z)
+        };
+
+        // Follows the null collisions through the unit sphere, absorbing media never scatter.
+        let samples = 100000;
+        let transmittance = |object: &dyn Hittable| {
+            (0..samples)
+                .map(|_| {
+                    let mut ray = ray;
+                    let mut weight = DVec3::ONE;
+
+                    while let Some(hit) = object.hit(ray, 0.001..f64::INFINITY) {
+                        let scattered = hit.material.scatter(ray, hit.clone()).unwrap();
+
+                        if scattered.pdf.is_some() {
+                            return DVec3::ZERO;
+                        }
+
+                        weight *= scattered.attenuation;
+                        ray.origin = hit.point;
+                    }
+
+                    weight
+                })
+                .sum::<DVec3>()
+                / samples as f64
+        };
+
+        let expected = (-2.0 * wavelengths.upsample(sigma_a)).exp();
+        let direct = transmittance(medium.as_ref());
+        let transformed = transmittance(&transform);
+
+        assert!(direct.abs_diff_eq(expected, 0.02), "{direct} {expected}");
+        assert!(
+            transformed.abs_diff_eq(expected, 0.02),
+            "{transformed} {expected}"
+        );
+    }
+}
@@ -0,0 +1,382 @@
+use std::{ops::Range, sync::Arc};
+
+use crate::{
+    bounding_volume::BoundingVolumeHierarchyNode,
+    hittable::{AxisAlignedBoundingBox, HitRecord, Hittable},
+    material::Material,
+    ray::Ray,
+};
+use glam::{DVec2, DVec3};
+use rand::Rng;
+
+#[derive(Debug)]
+pub struct Triangle {
+    positions: [DVec3; 3],
+    normals: Option<[DVec3; 3]>,
+    uvs: Option<[DVec2; 3]>,
+    bounding_box: AxisAlignedBoundingBox,
+    material: Arc<dyn Material>,
+}
+
+impl Triangle {
+    pub fn new(positions: [DVec3; 3], material: Arc<dyn Material>) -> Self {
+        Self {
+            positions,
+            normals: None,
+            uvs: None,
+            bounding_box: triangle_bounding_box(&positions),
+            material,
+        }
+    }
+
+    pub fn with_normals(mut self, normals: [DVec3; 3]) -> Self {
+        self.normals = Some(normals);
+        self
+    }
+
+    pub fn with_uvs(mut self, uvs: [DVec2; 3]) -> Self {
+        self.uvs = Some(uvs);
+        self
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, ray: Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let (t, barycentric) = intersect(ray, t_range, &self.positions)?;
+
+        Some(hit_record(
+            ray,
+            t,
+            barycentric,
+            &self.positions,
+            self.normals.as_ref(),
+            self.uvs.as_ref(),
+            self.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> &AxisAlignedBoundingBox {
+        &self.bounding_box
+    }
+
+    fn pdf_value(&self, origin: DVec3, direction: DVec3) -> f64 {
+        pdf_value(&self.positions, origin, direction)
+    }
+
+    fn random(&self, origin: DVec3) -> DVec3 {
+        random_point(&self.positions) - origin
+    }
+}
+
+/// An indexed triangle mesh whose triangles share one set of vertex buffers.
+///
+/// `normals` and `uvs` are either empty or hold one entry per position.
+#[derive(Debug)]
+pub struct TriangleMesh {
+    triangles: BoundingVolumeHierarchyNode<MeshTriangle>,
+}
+
+#[derive(Debug)]
+struct MeshData {
+    positions: Vec<DVec3>,
+    normals: Vec<DVec3>,
+    uvs: Vec<DVec2>,
+    material: Arc<dyn Material>,
+}
+
+impl TriangleMesh {
+    pub fn new(
+        positions: Vec<DVec3>,
+        normals: Vec<DVec3>,
+        uvs: Vec<DVec2>,
+        indices: Vec<[usize; 3]>,
+        material: Arc<dyn Material>,
+    ) -> Self {
+        assert!(normals.is_empty() || normals.len() == positions.len());
+        assert!(uvs.is_empty() || uvs.len() == positions.len());
+        assert!(!indices.is_empty());
+
+        let mesh = Arc::new(MeshData {
+            positions,
+            normals,
+            uvs,
+            material,
+        });
+
+        let triangles = indices
+            .into_iter()
+            .map(|indices| {
+                let bounding_box = triangle_bounding_box(&vertices(indices, &mesh.positions));
+
+                MeshTriangle {
+                    mesh: mesh.clone(),
+                    indices,
+                    bounding_box,
+                }
+            })
+            .collect();
+
+        Self {
+            triangles: BoundingVolumeHierarchyNode::new(triangles),
+        }
+    }
+}
+
+/// As a light every triangle is equally likely to be sampled, whatever its size.
+impl Hittable for TriangleMesh {
+    fn hit(&self, ray: Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        self.triangles.hit(ray, t_range)
+    }
+
+    fn bounding_box(&self) -> &AxisAlignedBoundingBox {
+        self.triangles.bounding_box()
+    }
+
+    fn pdf_value(&self, origin: DVec3, direction: DVec3) -> f64 {
+        self.triangles.pdf_value(origin, direction)
+    }
+
+    fn random(&self, origin: DVec3) -> DVec3 {
+        self.triangles.random(origin)
+    }
+}
+
+#[derive(Debug)]
+struct MeshTriangle {
+    mesh: Arc<MeshData>,
+    indices: [usize; 3],
+    bounding_box: AxisAlignedBoundingBox,
+}
+
+impl Hittable for MeshTriangle {
+    fn hit(&self, ray: Ray, t_range: Range<f64>) -> Option<HitRecord> {
+        let positions = vertices(self.indices, &self.mesh.positions);
+        let (t, barycentric) = intersect(ray, t_range, &positions)?;
+
+        Some(hit_record(
+            ray,
+            t,
+            barycentric,
+            &positions,
+            optional_vertices(self.indices, &self.mesh.normals).as_ref(),
+            optional_vertices(self.indices, &self.mesh.uvs).as_ref(),
+            self.mesh.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> &AxisAlignedBoundingBox {
+        &self.bounding_box
+    }
+
+    fn pdf_value(&self, origin: DVec3, direction: DVec3) -> f64 {
+        pdf_value(
+            &vertices(self.indices, &self.mesh.positions),
+            origin,
+            direction,
+        )
+    }
+
+    fn random(&self, origin: DVec3) -> DVec3 {
+        random_point(&vertices(self.indices, &self.mesh.positions)) - origin
+    }
+}
+
+fn vertices<V: Copy>(indices: [usize; 3], buffer: &[V]) -> [V; 3] {
+    indices.map(|index| buffer[index])
+}
+
+fn optional_vertices<V: Copy>(indices: [usize; 3], buffer: &[V]) -> Option<[V; 3]> {
+    if buffer.is_empty() {
+        None
+    } else {
+        Some(vertices(indices, buffer))
+    }
+}
+
+/// Solid angle density of [`random_point`] seen from `origin`.
+fn pdf_value(positions: &[DVec3; 3], origin: DVec3, direction: DVec3) -> f64 {
+    let Some((t, _)) = intersect(Ray::new(origin, direction), 0.001..f64::INFINITY, positions)
+    else {
+        return 0.0;
+    };
+
+    let normal = (positions[1] - positions[0]).cross(positions[2] - positions[0]);
+    let area = 0.5 * normal.length();
+
+    let distance_squared = t * t * direction.length_squared();
+    let cosine = (direction.dot(normal) / (direction.length() * normal.length())).abs();
+
+    distance_squared / (cosine * area)
+}
+
+/// A uniformly distributed point on the triangle.
+fn random_point(positions: &[DVec3; 3]) -> DVec3 {
+    let mut rand_thread = rand::thread_rng();
+    let r1: f64 = rand_thread.gen_range(0.0..1.0);
+    let r2: f64 = rand_thread.gen_range(0.0..1.0);
+
+    let sqrt_r1 = r1.sqrt();
+
+    (1.0 - sqrt_r1) * positions[0]
+        + sqrt_r1 * (1.0 - r2) * positions[1]
+        + sqrt_r1 * r2 * positions[2]
+}
+
+fn triangle_bounding_box(positions: &[DVec3; 3]) -> AxisAlignedBoundingBox {
+    let min = positions[0].min(positions[1]).min(positions[2]);
+    let max = positions[0].max(positions[1]).max(positions[2]);
+
+    AxisAlignedBoundingBox::from_corners(min, max).padded()
+}
+
+/// Möller–Trumbore intersection returning `t` and the barycentric weights of the
+/// second and third vertex.
+fn intersect(ray: Ray, t_range: Range<f64>, positions: &[DVec3; 3]) -> Option<(f64, DVec2)> {
+    let edge1 = positions[1] - positions[0];
+    let edge2 = positions[2] - positions[0];
+
+    let p = ray.direction.cross(edge2);
+    let determinant = edge1.dot(p);
+
+    if determinant.abs() < 1e-12 {
+        return None;
+    }
+
+    let inverse_determinant = determinant.recip();
+    let s = ray.origin - positions[0];
+    let u = s.dot(p) * inverse_determinant;
+
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let q = s.cross(edge1);
+    let v = ray.direction.dot(q) * inverse_determinant;
+
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let t = edge2.dot(q) * inverse_determinant;
+
+    if t_range.contains(&t) {
+        Some((t, DVec2::new(u, v)))
+    } else {
+        None
+    }
+}
+
+fn hit_record(
+    ray: Ray,
+    t: f64,
+    barycentric: DVec2,
+    positions: &[DVec3; 3],
+    normals: Option<&[DVec3; 3]>,
+    uvs: Option<&[DVec2; 3]>,
+    material: Arc<dyn Material>,
+) -> HitRecord {
+    let weights = [
+        1.0 - barycentric.x - barycentric.y,
+        barycentric.x,
+        barycentric.y,
+    ];
+
+    let geometric_normal = (positions[1] - positions[0])
+        .cross(positions[2] - positions[0])
+        .normalize();
+    let uv = match uvs {
+        Some(uvs) => uvs[0] * weights[0] + uvs[1] * weights[1] + uvs[2] * weights[2],
+        None => barycentric,
+    };
+
+    let mut hit_record = HitRecord::new(ray, ray.at(t), geometric_normal, t, uv, material);
+
+    if let Some(normals) = normals {
+        let shading_normal =
+            (normals[0] * weights[0] + normals[1] * weights[1] + normals[2] * weights[2])
+                .normalize();
+
+        hit_record.normal = if hit_record.front_face {
+            shading_normal
+        } else {
+            -shading_normal
+        };
+    }
+
+    hit_record
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hittable::assert_light_pdf, material::lambertian::Lambertian};
+
+    #[test]
+    fn triangle_hit() {
+        let material = Arc::new(Lambertian::with_solid(DVec3::ONE));
+        let positions = [DVec3::ZERO, DVec3::X, DVec3::Y];
+        let triangle = Triangle::new(positions, material.clone())
+            .with_uvs([DVec2::ZERO, DVec2::X, DVec2::Y])
+            .with_normals([DVec3::Z, DVec3::Z, DVec3::new(0.0, 1.0, 1.0)]);
+
+        let ray = Ray::new(DVec3::new(0.25, 0.25, 1.0), DVec3::NEG_Z);
+        let hit = triangle.hit(ray, 0.0..f64::INFINITY).unwrap();
+
+        assert!(hit.front_face);
+        assert_eq!(hit.t, 1.0);
+        assert_eq!(hit.uv, DVec2::new(0.25, 0.25));
+        assert!(hit.normal.y > 0.0 && hit.normal.is_normalized());
+
+        let ray_outside = Ray::new(DVec3::new(0.75, 0.75, 1.0), DVec3::NEG_Z);
+        assert!(triangle.hit(ray_outside, 0.0..f64::INFINITY).is_none());
+
+        let mesh = TriangleMesh::new(
+            vec![DVec3::ZERO, DVec3::X, DVec3::Y, DVec3::new(1.0, 1.0, 0.0)],
+            Vec::new(),
+            Vec::new(),
+            vec![[0, 1, 2], [1, 3, 2]],
+            material,
+        );
+
+        let hit = mesh.hit(ray_outside, 0.0..f64::INFINITY).unwrap();
+
+        assert_eq!(hit.t, 1.0);
+        assert_eq!(hit.normal, DVec3::Z);
+
+        let ray_back = Ray::new(DVec3::new(0.75, 0.75, -1.0), DVec3::Z);
+        let hit = mesh.hit(ray_back, 0.0..f64::INFINITY).unwrap();
+
+        assert!(!hit.front_face);
+        assert_eq!(hit.normal, DVec3::NEG_Z);
+    }
+
+    #[test]
+    fn triangle_pdf() {
+        let material = Arc::new(Lambertian::with_solid(DVec3::ONE));
+        let positions = [
+            DVec3::new(-1.0, 1.0, -1.0),
+            DVec3::new(1.0, 1.0, -1.0),
+            DVec3::new(0.0, 1.5, 1.0),
+        ];
+        let triangle = Triangle::new(positions, material.clone());
+
+        assert_light_pdf(&triangle, DVec3::ZERO);
+
+        // Meshes pick one of their triangles at random.
+        let mesh = TriangleMesh::new(
+            vec![
+                DVec3::new(-1.0, 1.0, -1.0),
+                DVec3::new(1.0, 1.0, -1.0),
+                DVec3::new(1.0, 1.0, 1.0),
+                DVec3::new(-1.0, 1.0, 1.0),
+                DVec3::new(-1.0, -1.0, 1.0),
+            ],
+            Vec::new(),
+            Vec::new(),
+            vec![[0, 1, 2], [0, 2, 3], [3, 2, 4]],
+            material,
+        );
+
+        assert_light_pdf(&mesh, DVec3::ZERO);
+    }
+}